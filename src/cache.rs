@@ -47,6 +47,12 @@ where
         self
     }
 
+    /// always fetch instead of reading the cache file, the result is still written to it
+    pub fn with_no_cache(mut self, no_cache: bool) -> Self {
+        self.no_cache = no_cache;
+        self
+    }
+
     pub fn with_fresh_file(mut self, path: PathBuf) -> Self {
         self.fresh_files.push(path);
         self
@@ -75,6 +81,22 @@ where
         Ok(val)
     }
 
+    /// like get_or_try_init, but offline the cache file is used regardless of its age and nothing
+    /// is fetched, errors if there is no cache file, see InstallOptions::offline
+    pub fn get_or_try_init_offline<F>(&self, offline: bool, fetch: F) -> Result<&T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        if !offline {
+            return self.get_or_try_init(fetch);
+        }
+        self.cache.get_or_try_init(|| {
+            let path = &self.cache_file_path;
+            ensure!(path.exists(), "required {} but offline", display_path(path));
+            self.parse()
+        })
+    }
+
     fn parse(&self) -> Result<T> {
         let path = &self.cache_file_path;
         trace!("reading {}", display_path(path));
@@ -150,4 +172,21 @@ mod tests {
         let val = cache.get_or_try_init(|| Ok(2)).unwrap();
         assert_eq!(val, &1);
     }
+
+    #[test]
+    fn test_cache_offline() {
+        let path = crate::dirs::CACHE.join("test-cache-offline.msgpack.z");
+        let cache = CacheManager::<i32>::new(path.clone());
+        cache.clear().unwrap();
+        let err = cache.get_or_try_init_offline(true, || Ok(1)).unwrap_err();
+        assert_str_eq!(
+            err.to_string(),
+            format!("required {} but offline", display_path(&path))
+        );
+        // an expired cache file is still used offline
+        cache.write(&2).unwrap();
+        let cache = CacheManager::<i32>::new(path).with_fresh_duration(Some(Duration::ZERO));
+        assert_eq!(cache.get_or_try_init_offline(true, || Ok(3)).unwrap(), &2);
+        cache.clear().unwrap();
+    }
 }
@@ -2,6 +2,8 @@ use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::path::PathBuf;
 
+use strum::IntoEnumIterator;
+
 use crate::dirs;
 use crate::forge::{unalias_forge, ForgeType};
 
@@ -13,16 +15,25 @@ pub struct ForgeArg {
     pub cache_path: PathBuf,
     pub installs_path: PathBuf,
     pub downloads_path: PathBuf,
+    /// overrides where the tool is fetched from, e.g.: `asdf:node[repo=https://...]`
+    pub source_url: Option<String>,
+    /// what the tool was written as, e.g.: "nodejs" for "node", this is what Display shows
+    pub input: String,
 }
 
 impl From<&str> for ForgeArg {
     fn from(s: &str) -> Self {
-        if let Some((forge_type, name)) = s.split_once(':') {
-            if let Ok(forge_type) = forge_type.parse() {
-                return Self::new(forge_type, name);
-            }
-        }
-        Self::new(ForgeType::Asdf, s)
+        let (s, source_url) = split_options(s);
+        let mut fa = match unalias_forge(s).split_once(':') {
+            Some((forge_type, name)) => match forge_type.parse() {
+                Ok(forge_type) => Self::new(forge_type, name),
+                Err(_) => Self::new(ForgeType::Asdf, s),
+            },
+            None => Self::new(ForgeType::Asdf, s),
+        };
+        fa.source_url = source_url;
+        fa.input = s.to_string();
+        fa
     }
 }
 impl From<&String> for ForgeArg {
@@ -33,7 +44,11 @@ impl From<&String> for ForgeArg {
 
 impl ForgeArg {
     pub fn new(forge_type: ForgeType, name: &str) -> Self {
-        let name = unalias_forge(name).to_string();
+        // registry names are tool names, e.g.: "cargo:ubi" is not the registry's "ubi"
+        let name = match forge_type {
+            ForgeType::Asdf => unalias_forge(name).to_string(),
+            _ => name.to_string(),
+        };
         let id = match forge_type {
             ForgeType::Asdf => name.clone(),
             forge_type => format!("{}:{}", forge_type.as_ref(), name),
@@ -46,13 +61,63 @@ impl ForgeArg {
             cache_path: dirs::CACHE.join(&pathname),
             installs_path: dirs::INSTALLS.join(&pathname),
             downloads_path: dirs::DOWNLOADS.join(&pathname),
+            source_url: None,
+            input: String::new(),
+        }
+    }
+}
+
+impl ForgeArg {
+    /// like `From<&str>` but fails if the backend before `:` is unknown instead of treating the
+    /// whole string as the name of an asdf plugin, e.g.: `crgo:eza`
+    pub fn parse(s: &str) -> eyre::Result<Self> {
+        let (name, _) = split_options(s);
+        if let Some((forge_type, _)) = name.split_once(':') {
+            if forge_type.parse::<ForgeType>().is_err() {
+                let closest = ForgeType::iter()
+                    .min_by_key(|ft| edit_distance(forge_type, ft.as_ref()))
+                    .unwrap();
+                bail!("unknown backend {forge_type} in {s}, did you mean {closest}?");
+            }
         }
+        Ok(s.into())
     }
 }
 
+/// levenshtein distance between a and b
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// splits the bracketed options off of a forge arg like `asdf:node[repo=https://...]`
+fn split_options(s: &str) -> (&str, Option<String>) {
+    let Some((s, options)) = s.strip_suffix(']').and_then(|s| s.split_once('[')) else {
+        return (s, None);
+    };
+    let source_url = options
+        .split(',')
+        .filter_map(|o| o.split_once('='))
+        .find(|(k, _)| k.trim() == "repo")
+        .map(|(_, v)| v.trim().to_string());
+    (s, source_url)
+}
+
 impl Display for ForgeArg {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.id)
+        match self.input.is_empty() {
+            true => write!(f, "{}", self.id),
+            false => write!(f, "{}", self.input),
+        }
     }
 }
 
@@ -90,14 +155,98 @@ mod tests {
         };
         let asdf = |s, id, name| t(s, id, name, ForgeType::Asdf);
         let cargo = |s, id, name| t(s, id, name, ForgeType::Cargo);
+        let go = |s, id, name| t(s, id, name, ForgeType::Go);
         let npm = |s, id, name| t(s, id, name, ForgeType::Npm);
+        let http = |s, id, name| t(s, id, name, ForgeType::Http);
+        let pipx = |s, id, name| t(s, id, name, ForgeType::Pipx);
 
         asdf("asdf:node", "node", "node");
         asdf("node", "node", "node");
         asdf("", "", "");
         cargo("cargo:eza", "cargo:eza", "eza");
+        go(
+            "go:github.com/foo/bar",
+            "go:github.com/foo/bar",
+            "github.com/foo/bar",
+        );
         npm("npm:@antfu/ni", "npm:@antfu/ni", "@antfu/ni");
         npm("npm:prettier", "npm:prettier", "prettier");
+        http("http:mytool", "http:mytool", "mytool");
+        pipx("pipx:poetry", "pipx:poetry", "poetry");
+        pipx("pipx:psf/black", "pipx:psf/black", "psf/black");
+    }
+
+    #[test]
+    fn test_forge_arg_parse() {
+        assert_str_eq!(ForgeArg::parse("cargo:eza").unwrap().id, "cargo:eza");
+        assert_str_eq!(ForgeArg::parse("node").unwrap().id, "node");
+        assert_str_eq!(
+            ForgeArg::parse("crgo:eza").unwrap_err().to_string(),
+            "unknown backend crgo in crgo:eza, did you mean cargo?"
+        );
+    }
+
+    #[test]
+    fn test_forge_arg_asdf_normalization() {
+        let fa = |s: &str| -> ForgeArg { s.into() };
+        assert_eq!(fa("asdf:node"), fa("node"));
+        assert_eq!(fa("asdf:nodejs"), fa("node"));
+        let set: std::collections::HashSet<_> = [fa("asdf:node"), fa("node")].into();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn test_forge_arg_source_url() {
+        let t = |s: &str, id, name, url: Option<&str>| {
+            let fa: ForgeArg = s.into();
+            assert_str_eq!(fa.id, id);
+            assert_str_eq!(fa.name, name);
+            assert_eq!(fa.source_url.as_deref(), url);
+        };
+        let url = "https://github.com/asdf-vm/asdf-nodejs";
+        t(
+            "asdf:node[repo=https://github.com/asdf-vm/asdf-nodejs]",
+            "node",
+            "node",
+            Some(url),
+        );
+        t(
+            "node[repo=https://github.com/asdf-vm/asdf-nodejs]",
+            "node",
+            "node",
+            Some(url),
+        );
+        t(
+            "npm:prettier[repo=https://npm.example.com]",
+            "npm:prettier",
+            "prettier",
+            Some("https://npm.example.com"),
+        );
+        t("cargo:eza[other=1]", "cargo:eza", "eza", None);
+        t("cargo:eza", "cargo:eza", "eza", None);
+        let fa: ForgeArg = "asdf:node[repo=https://github.com/asdf-vm/asdf-nodejs]".into();
+        assert_eq!(fa, "node".into());
+    }
+
+    #[test]
+    fn test_forge_arg_display() {
+        let fa: ForgeArg = "ubi".into();
+        assert_str_eq!(fa.to_string(), "ubi");
+        assert_str_eq!(format!("{fa:?}"), r#"ForgeArg("cargo:ubi")"#);
+        assert_str_eq!(fa.id, "cargo:ubi");
+        assert_eq!(fa.forge_type, ForgeType::Cargo);
+        assert_eq!(fa.installs_path, dirs::INSTALLS.join("cargo-ubi"));
+        assert_eq!(fa.cache_path, dirs::CACHE.join("cargo-ubi"));
+        assert_eq!(fa, "cargo:ubi".into());
+
+        let fa: ForgeArg = "asdf:node[repo=https://github.com/asdf-vm/asdf-nodejs]".into();
+        assert_str_eq!(fa.to_string(), "asdf:node");
+        let fa: ForgeArg = "cargo:eza".into();
+        assert_str_eq!(fa.to_string(), "cargo:eza");
+        assert_str_eq!(
+            ForgeArg::new(ForgeType::Cargo, "eza").to_string(),
+            "cargo:eza"
+        );
     }
 
     #[test]
@@ -33,7 +33,7 @@ impl FromStr for ToolArg {
     fn from_str(input: &str) -> eyre::Result<Self> {
         let (forge_input, version) = parse_input(input);
 
-        let forge: ForgeArg = forge_input.into();
+        let forge = ForgeArg::parse(forge_input)?;
         let version_type = match version.as_ref() {
             Some(version) => version.parse()?,
             None => ToolVersionType::Version(String::from("latest")),
@@ -25,6 +25,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   $ <bold>mise backends ls</bold>
   cargo
   go
+  http
   npm
   pipx
   ubi
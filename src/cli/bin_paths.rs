@@ -1,19 +1,29 @@
 use eyre::Result;
 
+use crate::cli::args::ForgeArg;
 use crate::config::Config;
 use crate::toolset::ToolsetBuilder;
 
 /// List all the active runtime bin paths
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment)]
-pub struct BinPaths {}
+pub struct BinPaths {
+    /// Only show bin paths for this tool
+    /// e.g.: node, cargo:eza
+    #[clap(verbatim_doc_comment)]
+    tool: Option<ForgeArg>,
+}
 
 impl BinPaths {
     pub fn run(self) -> Result<()> {
         let config = Config::try_get()?;
         let ts = ToolsetBuilder::new().build(&config)?;
         ts.notify_if_versions_missing();
-        for p in ts.list_paths() {
+        let paths = match &self.tool {
+            Some(fa) => ts.list_bin_paths_for(fa),
+            None => ts.list_paths(),
+        };
+        for p in paths {
             miseprintln!("{}", p.display());
         }
         Ok(())
@@ -30,4 +40,10 @@ mod tests {
         ~/data/installs/dummy/ref-master/bin
         "###);
     }
+
+    #[test]
+    fn test_bin_paths_tool() {
+        assert_cli!("i");
+        assert_cli_snapshot!("bin-paths", "tiny", @"~/data/installs/tiny/3/bin");
+    }
 }
@@ -18,12 +18,23 @@ pub struct Current {
     /// e.g.: ruby, node, cargo:eza, npm:prettier, etc.
     #[clap()]
     plugin: Option<ForgeArg>,
+
+    /// Output in JSON format
+    #[clap(short = 'J', long, verbatim_doc_comment)]
+    json: bool,
 }
 
 impl Current {
     pub fn run(self) -> Result<()> {
         let config = Config::try_get()?;
-        let ts = ToolsetBuilder::new().build(&config)?;
+        let mut ts = ToolsetBuilder::new().build(&config)?;
+        if self.json {
+            if let Some(fa) = &self.plugin {
+                ts.versions.retain(|k, _| k == fa);
+            }
+            miseprintln!("{}", serde_json::to_string_pretty(&ts.to_json()?)?);
+            return Ok(());
+        }
         match &self.plugin {
             Some(fa) => {
                 let plugin = forge::get(fa);
@@ -109,6 +120,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     # can output multiple versions
     $ <bold>mise current python</bold>
     3.11.0 3.10.0
+
+    $ <bold>mise current --json</bold>
+    {"node": {"source": {...}, "requests": ["20"], "versions": [{"version": "20.0.0", ...}]}}
 "#
 );
 
@@ -129,6 +143,15 @@ mod tests {
         assert_cli_snapshot!("current", "tiny", @"3.1.0");
     }
 
+    #[test]
+    fn test_current_json() {
+        let output = assert_cli!("current", "tiny", "--json");
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let tv = &json["tiny"]["versions"][0];
+        assert_eq!(tv["version"], "3.1.0");
+        assert_eq!(tv["installed"], true);
+    }
+
     #[test]
     fn test_current_missing() {
         assert_cli!("uninstall", "dummy@1.0.1");
@@ -1,3 +1,4 @@
+use std::env::split_paths;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
 
@@ -33,7 +34,9 @@ impl Envrc {
         for cf in config.config_files.keys() {
             writeln!(file, "watch_file {}", cf.to_string_lossy())?;
         }
-        for (k, v) in ts.env(config)? {
+        let mut env = ts.env(config)?;
+        let remove_paths = env.remove("MISE_REMOVE_PATH");
+        for (k, v) in env {
             if k == "PATH" {
                 writeln!(file, "PATH_add {}", v)?;
             } else {
@@ -48,6 +51,11 @@ impl Envrc {
         for path in ts.list_paths().into_iter().rev() {
             writeln!(file, "PATH_add {}", path.to_string_lossy())?;
         }
+        if let Some(remove_paths) = remove_paths {
+            for path in split_paths(&remove_paths) {
+                writeln!(file, "PATH_rm {}", path.to_string_lossy())?;
+            }
+        }
 
         miseprintln!("{}", envrc_path.to_string_lossy());
         Ok(())
@@ -16,7 +16,7 @@ use crate::git::Git;
 use crate::plugins::core::CORE_PLUGINS;
 use crate::plugins::PluginType;
 use crate::shell::ShellType;
-use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::toolset::{PathIssue, ToolSourceKind, Toolset, ToolsetBuilder};
 use crate::ui::style;
 use crate::{cmd, dirs, duration, env, file, forge, shims};
 
@@ -122,9 +122,17 @@ impl Doctor {
             ));
         }
 
-        match ToolsetBuilder::new().build(config) {
+        let tsb = ToolsetBuilder::new()
+            .with_best_effort(ToolSourceKind::GlobalConfig)
+            .with_best_effort(ToolSourceKind::Environment);
+        match tsb.build(config) {
             Ok(ts) => {
+                for source in &ts.skipped_sources {
+                    self.warnings
+                        .push(format!("failed to load tools from {source}"));
+                }
                 self.analyze_shims(&ts);
+                self.analyze_path(&ts)?;
                 self.analyze_toolset(&ts)?;
             }
             Err(err) => self.errors.push(format!("failed to load toolset: {}", err)),
@@ -133,6 +141,21 @@ impl Doctor {
         Ok(())
     }
 
+    fn analyze_path(&mut self, ts: &Toolset) -> eyre::Result<()> {
+        let issues = ts.doctor_path();
+        let body = match issues.is_empty() {
+            true => "(none)".to_string(),
+            false => issues.iter().join("\n"),
+        };
+        section("path_issues", body)?;
+        for issue in issues {
+            if !matches!(issue, PathIssue::ShimsNotOnPath(_)) {
+                self.warnings.push(issue.to_string());
+            }
+        }
+        Ok(())
+    }
+
     fn analyze_toolset(&mut self, ts: &Toolset) -> eyre::Result<()> {
         let tools = ts
             .list_current_versions()
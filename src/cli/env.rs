@@ -1,7 +1,10 @@
+use std::env::join_paths;
+
 use eyre::Result;
 
 use crate::cli::args::ToolArg;
 use crate::config::Config;
+use crate::env_diff::EnvDiffOperation;
 use crate::shell::{get_shell, ShellType};
 use crate::toolset::{InstallOptions, Toolset, ToolsetBuilder};
 
@@ -23,6 +26,14 @@ pub struct Env {
     /// Shell type to generate environment variables for
     #[clap(long, short, overrides_with = "json")]
     shell: Option<ShellType>,
+
+    /// Only output env vars that differ from the current environment
+    #[clap(long)]
+    diff: bool,
+
+    /// Show which tool or config set each env var
+    #[clap(long, conflicts_with_all = ["json", "shell", "diff"])]
+    sources: bool,
 }
 
 impl Env {
@@ -32,7 +43,11 @@ impl Env {
         ts.install_arg_versions(&config, &InstallOptions::new())?;
         ts.notify_if_versions_missing();
 
-        if self.json {
+        if self.sources {
+            self.output_sources(&config, ts)
+        } else if self.diff {
+            self.output_diff(&config, ts)
+        } else if self.json {
             self.output_json(&config, ts)
         } else {
             self.output_shell(&config, ts)
@@ -45,6 +60,13 @@ impl Env {
         Ok(())
     }
 
+    fn output_sources(&self, config: &Config, ts: Toolset) -> Result<()> {
+        for (k, (v, source)) in ts.env_with_sources(config)? {
+            miseprintln!("{k}={v} # {source}");
+        }
+        Ok(())
+    }
+
     fn output_shell(&self, config: &Config, ts: Toolset) -> Result<()> {
         let default_shell = get_shell(Some(ShellType::Bash)).unwrap();
         let shell = get_shell(self.shell).unwrap_or(default_shell);
@@ -55,6 +77,31 @@ impl Env {
         }
         Ok(())
     }
+
+    fn output_diff(&self, config: &Config, ts: Toolset) -> Result<()> {
+        let diff = ts.env_diff(config)?;
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&diff)?);
+            return Ok(());
+        }
+        let default_shell = get_shell(Some(ShellType::Bash)).unwrap();
+        let shell = get_shell(self.shell).unwrap_or(default_shell);
+        for patch in diff.to_patches() {
+            match patch {
+                EnvDiffOperation::Add(k, v) | EnvDiffOperation::Change(k, v) => {
+                    miseprint!("{}", shell.set_env(&k, &v))?;
+                }
+                EnvDiffOperation::Remove(k) => {
+                    miseprint!("{}", shell.unset_env(&k))?;
+                }
+            }
+        }
+        if !diff.path.is_empty() {
+            let path = join_paths(&diff.path)?;
+            miseprint!("{}", shell.prepend_env("PATH", &path.to_string_lossy()))?;
+        }
+        Ok(())
+    }
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -64,6 +111,8 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>eval "$(mise env -s zsh)"</bold>
     $ <bold>mise env -s fish | source</bold>
     $ <bold>execx($(mise env -s xonsh))</bold>
+    $ <bold>mise env --diff</bold>
+    $ <bold>mise env --sources</bold>
 "#
 );
 
@@ -124,6 +173,26 @@ mod tests {
         assert!(stdout.contains("export PATH="));
     }
 
+    #[test]
+    fn test_env_diff() {
+        let stdout = assert_cli!("env", "tiny@2", "--diff", "-s", "bash");
+        assert_str_eq!(grep(stdout.clone(), "JDXCODE"), "export JDXCODE_TINY=2.1.0");
+        let path = grep(stdout, "export PATH=");
+        assert!(path.ends_with(":$PATH\""));
+        assert!(path.contains(
+            dirs::DATA
+                .join("installs/tiny/2/bin")
+                .to_string_lossy()
+                .as_ref()
+        ));
+    }
+
+    #[test]
+    fn test_env_sources() {
+        let stdout = assert_cli!("env", "tiny@2", "--sources");
+        assert_str_eq!(grep(stdout, "JDXCODE"), "JDXCODE_TINY=2.1.0 # tiny");
+    }
+
     #[test]
     fn test_env_json() {
         assert_cli_snapshot!("env", "-J");
@@ -5,7 +5,7 @@ use clap::ValueHint;
 use duct::IntoExecutablePath;
 use eyre::Result;
 
-use crate::cli::args::ToolArg;
+use crate::cli::args::{ForgeArg, ToolArg};
 #[cfg(test)]
 use crate::cmd;
 use crate::config::Config;
@@ -46,6 +46,10 @@ pub struct Exec {
     /// Sets --jobs=1
     #[clap(long, overrides_with = "jobs")]
     pub raw: bool,
+
+    /// Leave out the env vars and bin paths of these tools
+    #[clap(long, value_name = "TOOL")]
+    pub without: Vec<ForgeArg>,
 }
 
 impl Exec {
@@ -60,12 +64,21 @@ impl Exec {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: false,
+            ..InstallOptions::new()
         };
         ts.install_arg_versions(&config, &opts)?;
         ts.notify_if_versions_missing();
 
         let (program, args) = parse_command(&env::SHELL, &self.command, &self.c);
-        let env = ts.env_with_path(&config)?;
+        let env = match self.without.is_empty() {
+            true => ts.env_with_path(&config)?,
+            // the pristine env is included so vars of the excluded tools which were already set,
+            // e.g.: by `mise activate`, are reset
+            false => {
+                let without = self.without.iter().cloned().collect();
+                ts.env_excluding(&config, &without)?
+            }
+        };
 
         self.exec(program, args, env)
     }
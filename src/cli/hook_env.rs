@@ -11,7 +11,7 @@ use crate::direnv::DirenvDiff;
 use crate::env::{TERM_WIDTH, __MISE_DIFF};
 use crate::env_diff::{EnvDiff, EnvDiffOperation};
 use crate::shell::{get_shell, ShellType};
-use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::toolset::{ToolSourceKind, Toolset, ToolsetBuilder};
 use crate::{env, hook_env};
 
 /// [internal] called by activate hook to update env vars directory change
@@ -38,11 +38,19 @@ impl HookEnv {
         if hook_env::should_exit_early(&watch_files) {
             return Ok(());
         }
-        let ts = ToolsetBuilder::new().build(&config)?;
+        // an invalid tool in the global config or an env var should not break the prompt of every
+        // project, a global config which doesn't parse still fails Config::load
+        let ts = ToolsetBuilder::new()
+            .with_best_effort(ToolSourceKind::GlobalConfig)
+            .with_best_effort(ToolSourceKind::Environment)
+            .build(&config)?;
         let shell = get_shell(self.shell).expect("no shell provided, use `--shell=zsh`");
         miseprint!("{}", hook_env::clear_old_env(&*shell))?;
         let mut env = ts.env(&config)?;
         let env_path = env.remove("PATH");
+        // MISE_REMOVE_PATH is only applied by env_with_path, removing paths from the
+        // shell's PATH couldn't be undone when leaving the directory
+        env.remove("MISE_REMOVE_PATH");
         let mut diff = EnvDiff::new(&env::PRISTINE_ENV, env);
         let mut patches = diff.to_patches();
 
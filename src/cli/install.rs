@@ -4,7 +4,8 @@ use itertools::Itertools;
 use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::toolset::{
-    InstallOptions, ToolRequest, ToolVersion, ToolVersionOptions, Toolset, ToolsetBuilder,
+    InstallOptions, InstallSummary, Lockfile, ToolRequest, ToolRequestSet, ToolVersion,
+    ToolVersionOptions, Toolset, ToolsetBuilder,
 };
 use crate::ui::multi_progress_report::MultiProgressReport;
 
@@ -29,6 +30,19 @@ pub struct Install {
     #[clap(long, short, requires = "tool")]
     force: bool,
 
+    /// Show what would be installed without installing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+
+    /// Keep installing the other tools if one fails
+    #[clap(long, short = 'k')]
+    keep_going: bool,
+
+    /// Write the exact versions back to the config files the tools are defined in
+    /// e.g.: `node = "20"` becomes `node = "20.12.2"`
+    #[clap(long, conflicts_with_all = ["tool", "dry_run"], verbatim_doc_comment)]
+    pin: bool,
+
     /// Number of jobs to run in parallel
     /// [default: 4]
     #[clap(long, short, env = "MISE_JOBS", verbatim_doc_comment)]
@@ -57,7 +71,6 @@ impl Install {
         Ok(())
     }
     fn install_runtimes(&self, config: &Config, runtimes: &[ToolArg]) -> Result<Vec<ToolVersion>> {
-        let mpr = MultiProgressReport::get();
         let mut ts = ToolsetBuilder::new().build(config)?;
         let tool_versions = self.get_requested_tool_versions(&ts, runtimes)?;
         if tool_versions.is_empty() {
@@ -65,7 +78,29 @@ impl Install {
             warn!("specify a version with `mise install <PLUGIN>@<VERSION>`");
             return Ok(vec![]);
         }
-        ts.install_versions(config, tool_versions, &mpr, &self.install_opts())
+        self.install_versions(config, &mut ts, tool_versions)
+    }
+
+    /// prints a summary once more than one tool was installed
+    fn install_versions(
+        &self,
+        config: &Config,
+        ts: &mut Toolset,
+        versions: Vec<ToolRequest>,
+    ) -> Result<Vec<ToolVersion>> {
+        let mpr = MultiProgressReport::get();
+        let mut summary = InstallSummary::default();
+        let installed = ts.install_versions_with_summary(
+            config,
+            versions,
+            &mpr,
+            &self.install_opts(),
+            &mut summary,
+        );
+        if summary.total() > 1 {
+            info!("{summary}");
+        }
+        installed
     }
 
     fn install_opts(&self) -> InstallOptions {
@@ -74,6 +109,9 @@ impl Install {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: true,
+            dry_run: self.dry_run,
+            keep_going: self.keep_going,
+            ..InstallOptions::new()
         }
     }
 
@@ -118,15 +156,58 @@ impl Install {
     }
 
     fn install_missing_runtimes(&self, config: &Config) -> eyre::Result<Vec<ToolVersion>> {
-        let trs = config.get_tool_request_set()?;
+        let mut trs = config.get_tool_request_set()?.clone();
+        let lockfile = Lockfile::path(config);
+        if let Some(path) = &lockfile {
+            trs = trs.lock(&ToolRequestSet::from_lockfile(path)?);
+        }
         let versions = trs.missing_tools().into_iter().cloned().collect_vec();
-        if versions.is_empty() {
-            info!("all runtimes are installed");
-            return Ok(vec![]);
+        let installed = match versions.is_empty() {
+            true => {
+                info!("all runtimes are installed");
+                vec![]
+            }
+            false => {
+                let mut ts = Toolset::default();
+                ts.merge_request_set(trs);
+                self.install_versions(config, &mut ts, versions)?
+            }
+        };
+        if let Some(path) = lockfile.filter(|_| !self.dry_run) {
+            ToolsetBuilder::new().build(config)?.write_lockfile(&path)?;
         }
-        let mpr = MultiProgressReport::get();
-        let mut ts: Toolset = trs.clone().into();
-        ts.install_versions(config, versions, &mpr, &self.install_opts())
+        if self.pin {
+            self.pin_versions(config, &installed)?;
+        }
+        Ok(installed)
+    }
+
+    /// only tools with a single version or prefix request are pinned
+    /// the versions which were just installed are pinned as they were resolved for the install,
+    /// the others were already installed so their resolved version is
+    fn pin_versions(&self, config: &Config, installed: &[ToolVersion]) -> Result<()> {
+        let ts = ToolsetBuilder::new().build(config)?;
+        for (fa, tvl) in &ts.versions {
+            let [tv] = tvl.versions.as_slice() else {
+                continue;
+            };
+            let pinnable = matches!(
+                tv.request,
+                ToolRequest::Version { .. } | ToolRequest::Prefix { .. }
+            );
+            let version = installed
+                .iter()
+                .find(|installed| &installed.forge == fa)
+                .map_or(&tv.version, |installed| &installed.version);
+            if !pinnable || &tv.request.version() == version {
+                continue;
+            }
+            match tvl.source.update_version(fa, version) {
+                Ok(()) => info!("pinned {fa}@{version} in {}", tvl.source),
+                Err(err) => warn!("{err:#}"),
+            }
+        }
+        Ok(())
     }
 }
 
@@ -172,6 +253,13 @@ mod tests {
         assert_cli!("global", "--unset", "dummy");
     }
 
+    #[test]
+    fn test_install_dry_run() {
+        assert_cli!("uninstall", "tiny@1.0.0");
+        assert_cli!("install", "--dry-run", "tiny@1.0.0");
+        assert!(!dirs::INSTALLS.join("tiny/1.0.0").exists());
+    }
+
     #[test]
     fn test_install_nothing() {
         // this doesn't do anything since dummy isn't specified
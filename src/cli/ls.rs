@@ -14,7 +14,7 @@ use versions::Versioning;
 use crate::cli::args::ForgeArg;
 use crate::config::Config;
 use crate::forge;
-use crate::forge::Forge;
+use crate::forge::{Forge, ForgeType};
 use crate::toolset::{ToolSource, ToolVersion, ToolsetBuilder};
 use crate::ui::table;
 
@@ -64,6 +64,10 @@ pub struct Ls {
     #[clap(long, requires = "plugin")]
     prefix: Option<String>,
 
+    /// Only show tools of this backend type, e.g.: cargo or npm
+    #[clap(long)]
+    plugin_type: Option<ForgeType>,
+
     /// Don't display headers
     #[clap(long, alias = "no-headers", verbatim_doc_comment, conflicts_with_all = & ["json", "parseable"])]
     no_header: bool,
@@ -184,11 +188,18 @@ impl Ls {
         let mut versions: HashMap<(String, String), (Arc<dyn Forge>, ToolVersion)> = ts
             .list_installed_versions()?
             .into_iter()
+            .filter(|(p, _)| match self.plugin_type {
+                Some(ft) => p.fa().forge_type == ft,
+                None => true,
+            })
             .map(|(p, tv)| ((p.id().into(), tv.version.clone()), (p, tv)))
             .collect();
 
-        let active = ts
-            .list_current_versions()
+        let active = match self.plugin_type {
+            Some(ft) => ts.list_current_versions_by_type(ft),
+            None => ts.list_current_versions(),
+        };
+        let active = active
             .into_iter()
             .map(|(p, tv)| ((p.id().into(), tv.version.clone()), (p, tv)))
             .collect::<HashMap<(String, String), (Arc<dyn Forge>, ToolVersion)>>();
@@ -5,9 +5,10 @@ use itertools::Itertools;
 use rayon::prelude::*;
 
 use crate::cli::args::ToolArg;
+use crate::config::Config;
 use crate::forge;
 use crate::forge::Forge;
-use crate::toolset::ToolRequest;
+use crate::toolset::{ToolRequest, ToolsetBuilder};
 use crate::ui::multi_progress_report::MultiProgressReport;
 
 /// List runtime versions available for install
@@ -29,6 +30,10 @@ pub struct LsRemote {
     /// same as the first argument after the "@"
     #[clap(verbatim_doc_comment)]
     pub prefix: Option<String>,
+
+    /// Fetch the versions again instead of using the cache
+    #[clap(long)]
+    pub refresh: bool,
 }
 
 impl LsRemote {
@@ -49,13 +54,16 @@ impl LsRemote {
             _ => self.prefix.clone(),
         };
 
-        let versions = plugin.list_remote_versions()?;
-        let versions = match prefix {
-            Some(prefix) => versions
-                .into_iter()
-                .filter(|v| v.starts_with(&prefix))
-                .collect(),
-            None => versions,
+        let config = Config::get();
+        let ts = ToolsetBuilder::new().build(&config)?;
+        // oldest first so the newest versions are at the bottom of the terminal
+        let versions = ts
+            .list_remote_versions(plugin.fa(), self.refresh)?
+            .into_iter()
+            .rev();
+        let versions: Vec<_> = match prefix {
+            Some(prefix) => versions.filter(|v| v.starts_with(&prefix)).collect(),
+            None => versions.collect(),
         };
 
         for version in versions {
@@ -1,13 +1,11 @@
-use std::collections::HashSet;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashSet};
 
 use console::{pad_str, style, Alignment};
 use eyre::Result;
 
 use crate::cli::args::ToolArg;
 use crate::config::Config;
-use crate::forge::Forge;
-use crate::toolset::{ToolVersion, ToolsetBuilder};
+use crate::toolset::{OutdatedInfo, ToolsetBuilder, UpdateEntry};
 
 /// Shows outdated tool versions
 #[derive(Debug, clap::Args)]
@@ -35,13 +33,14 @@ impl Outdated {
             .collect::<HashSet<_>>();
         ts.versions
             .retain(|_, tvl| tool_set.is_empty() || tool_set.contains(&tvl.forge));
-        let outdated = ts.list_outdated_versions();
-        if outdated.is_empty() {
+        if ts.is_up_to_date() {
             info!("All tools are up to date");
-        } else if self.json {
-            self.display_json(outdated)?;
+            return Ok(());
+        }
+        if self.json {
+            self.display_json(ts.update_report()?)?;
         } else {
-            self.display(outdated)?;
+            self.display(ts.list_outdated_versions())?;
         }
 
         Ok(())
@@ -49,16 +48,16 @@ impl Outdated {
 
     fn display(&self, outdated: OutputVec) -> Result<()> {
         // TODO: make a generic table printer in src/ui/table
-        let plugins = outdated.iter().map(|(t, _, _)| t.id()).collect::<Vec<_>>();
+        let plugins = outdated.iter().map(|o| o.forge.id()).collect::<Vec<_>>();
         let requests = outdated
             .iter()
-            .map(|(_, tv, _)| tv.request.version())
+            .map(|o| o.tv.request.version())
             .collect::<Vec<_>>();
         let currents = outdated
             .iter()
-            .map(|(t, tv, _)| {
-                if t.is_version_installed(tv) {
-                    tv.version.clone()
+            .map(|o| {
+                if o.forge.is_version_installed(&o.tv) {
+                    o.tv.version.clone()
                 } else {
                     "MISSING".to_string()
                 }
@@ -66,7 +65,7 @@ impl Outdated {
             .collect::<Vec<_>>();
         let latests = outdated
             .iter()
-            .map(|(_, _, c)| c.clone())
+            .map(|o| o.latest.clone())
             .collect::<Vec<_>>();
         let plugin_width = plugins
             .iter()
@@ -111,22 +110,18 @@ impl Outdated {
         Ok(())
     }
 
-    fn display_json(&self, outdated: OutputVec) -> Result<()> {
-        let mut map = serde_json::Map::new();
-        for (t, tv, c) in outdated {
-            let mut inner = serde_json::Map::new();
-            inner.insert("requested".to_string(), tv.request.version().into());
-            inner.insert("current".to_string(), tv.version.clone().into());
-            inner.insert("latest".to_string(), c.into());
-            map.insert(t.id().to_string(), serde_json::Value::Object(inner));
-        }
-        let json = serde_json::Value::Object(map);
-        miseprintln!("{}", serde_json::to_string_pretty(&json)?);
+    fn display_json(&self, entries: Vec<UpdateEntry>) -> Result<()> {
+        let map = entries
+            .into_iter()
+            .filter(|e| e.update_available)
+            .map(|e| (e.forge.id().to_string(), e))
+            .collect::<BTreeMap<_, _>>();
+        miseprintln!("{}", serde_json::to_string_pretty(&map)?);
         Ok(())
     }
 }
 
-type OutputVec = Vec<(Arc<dyn Forge>, ToolVersion, String)>;
+type OutputVec = Vec<OutdatedInfo>;
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
@@ -1,16 +1,10 @@
-use std::collections::BTreeMap;
-use std::sync::Arc;
-
 use console::style;
 use eyre::Result;
 
 use crate::cli::args::ForgeArg;
 use crate::config::tracking::Tracker;
-use crate::config::{Config, Settings};
-use crate::forge::Forge;
-use crate::toolset::{ToolVersion, ToolsetBuilder};
-use crate::ui::multi_progress_report::MultiProgressReport;
-use crate::ui::prompt;
+use crate::config::Config;
+use crate::toolset::{PruneOptions, ToolsetBuilder};
 
 use super::trust::Trust;
 
@@ -65,43 +59,11 @@ impl Prune {
     fn prune_tools(&self) -> Result<()> {
         let config = Config::try_get()?;
         let ts = ToolsetBuilder::new().build(&config)?;
-        let mut to_delete = ts
-            .list_installed_versions()?
-            .into_iter()
-            .map(|(p, tv)| (tv.to_string(), (p, tv)))
-            .collect::<BTreeMap<String, (Arc<dyn Forge>, ToolVersion)>>();
-
-        if let Some(forges) = &self.plugin {
-            to_delete.retain(|_, (_, tv)| forges.contains(&tv.forge));
-        }
-
-        for cf in config.get_tracked_config_files()?.values() {
-            let mut ts = cf.to_toolset()?.clone();
-            if let Err(err) = ts.resolve() {
-                warn!("failed to resolve toolset from {cf}: {err:#}");
-            }
-            for (_, tv) in ts.list_current_versions() {
-                to_delete.remove(&tv.to_string());
-            }
-        }
-
-        self.delete(to_delete.into_values().collect())
-    }
-
-    fn delete(&self, to_delete: Vec<(Arc<dyn Forge>, ToolVersion)>) -> Result<()> {
-        let settings = Settings::try_get()?;
-        let mpr = MultiProgressReport::get();
-        for (p, tv) in to_delete {
-            let mut prefix = tv.style();
-            if self.dry_run {
-                prefix = format!("{} {} ", prefix, style("[dryrun]").bold());
-            }
-            let pr = mpr.add(&prefix);
-            if self.dry_run || settings.yes || prompt::confirm(&format!("remove {} ?", &tv))? {
-                p.uninstall_version(&tv, pr.as_ref(), self.dry_run)?;
-                pr.finish();
-            }
-        }
+        let opts = PruneOptions {
+            dry_run: self.dry_run,
+            forges: self.plugin.clone(),
+        };
+        ts.prune(&config, &opts)?;
         Ok(())
     }
 }
@@ -32,6 +32,10 @@ impl Reshim {
     pub fn run(self) -> Result<()> {
         let config = Config::try_get()?;
         let ts = ToolsetBuilder::new().build(&config)?;
+        if !ts.needs_reshim() {
+            // avoids taking the shims lock, reshim is run on every prompt by some setups
+            return Ok(());
+        }
 
         shims::reshim(&ts)
     }
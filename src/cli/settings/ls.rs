@@ -68,6 +68,7 @@ mod tests {
         color = true
         disable_default_shorthands = false
         disable_tools = []
+        env_tool_override = []
         experimental = true
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
@@ -75,23 +76,30 @@ mod tests {
         go_set_gopath = false
         go_set_goroot = true
         go_skip_checksum = false
+        hermetic_path = false
+        hermetic_path_base = ["/usr/bin", "/bin"]
+        install_retries = 0
         jobs = 2
         legacy_version_file = true
         legacy_version_file_disable_tools = []
         node_compile = false
         not_found_auto_install = true
+        offline = false
         paranoid = false
         plugin_autoupdate_last_check_duration = "20m"
+        prefer_stable = true
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
         raw = false
+        strict_option_vars = false
         trusted_config_paths = []
         verbose = true
         yes = true
 
         [status]
         missing_tools = "if_other_versions_installed"
+        missing_tools_ignore = []
         show_env = false
         show_tools = false
         "###);
@@ -110,6 +118,7 @@ mod tests {
         color
         disable_default_shorthands
         disable_tools
+        env_tool_override
         experimental
         go_default_packages_file
         go_download_mirror
@@ -117,21 +126,28 @@ mod tests {
         go_set_gopath
         go_set_goroot
         go_skip_checksum
+        hermetic_path
+        hermetic_path_base
+        install_retries
         jobs
         legacy_version_file
         legacy_version_file_disable_tools
         node_compile
         not_found_auto_install
+        offline
         paranoid
         plugin_autoupdate_last_check_duration
+        prefer_stable
         python_default_packages_file
         python_pyenv_repo
         quiet
         raw
         status
         status.missing_tools
+        status.missing_tools_ignore
         status.show_env
         status.show_tools
+        strict_option_vars
         trusted_config_paths
         verbose
         yes
@@ -28,6 +28,7 @@ impl SettingsSet {
             "color" => parse_bool(&self.value)?,
             "disable_default_shorthands" => parse_bool(&self.value)?,
             "disable_tools" => self.value.split(',').map(|s| s.to_string()).collect(),
+            "env_tool_override" => self.value.split(',').map(|s| s.to_string()).collect(),
             "experimental" => parse_bool(&self.value)?,
             "go_default_packages_file" => self.value.into(),
             "go_download_mirror" => self.value.into(),
@@ -36,20 +37,29 @@ impl SettingsSet {
             "go_set_gopath" => parse_bool(&self.value)?,
             "go_set_goroot" => parse_bool(&self.value)?,
             "go_skip_checksum" => parse_bool(&self.value)?,
+            "hermetic_path" => parse_bool(&self.value)?,
+            "hermetic_path_base" => self.value.split(':').map(|s| s.to_string()).collect(),
+            "install_retries" => parse_i64(&self.value)?,
+            "install_timeout" => self.value.into(),
             "jobs" => parse_i64(&self.value)?,
             "legacy_version_file" => parse_bool(&self.value)?,
+            "max_download_size" => parse_i64(&self.value)?,
             "node_compile" => parse_bool(&self.value)?,
             "not_found_auto_install" => parse_bool(&self.value)?,
+            "offline" => parse_bool(&self.value)?,
             "paranoid" => parse_bool(&self.value)?,
             "plugin_autoupdate_last_check_duration" => self.value.into(),
+            "prefer_stable" => parse_bool(&self.value)?,
             "python_compile" => parse_bool(&self.value)?,
             "python_venv_auto_create" => parse_bool(&self.value)?,
             "quiet" => parse_bool(&self.value)?,
             "raw" => parse_bool(&self.value)?,
             "shorthands_file" => self.value.into(),
             "status.missing_tools" => self.value.into(),
+            "status.missing_tools_ignore" => self.value.split(',').map(|s| s.to_string()).collect(),
             "status.show_env" => parse_bool(&self.value)?,
             "status.show_tools" => parse_bool(&self.value)?,
+            "strict_option_vars" => parse_bool(&self.value)?,
             "task_output" => self.value.into(),
             "trusted_config_paths" => self.value.split(':').map(|s| s.to_string()).collect(),
             "verbose" => parse_bool(&self.value)?,
@@ -132,6 +142,7 @@ pub mod tests {
         color = true
         disable_default_shorthands = false
         disable_tools = []
+        env_tool_override = []
         experimental = true
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
@@ -139,23 +150,30 @@ pub mod tests {
         go_set_gopath = false
         go_set_goroot = true
         go_skip_checksum = false
+        hermetic_path = false
+        hermetic_path_base = ["/usr/bin", "/bin"]
+        install_retries = 0
         jobs = 2
         legacy_version_file = false
         legacy_version_file_disable_tools = []
         node_compile = false
         not_found_auto_install = true
+        offline = false
         paranoid = false
         plugin_autoupdate_last_check_duration = "1"
+        prefer_stable = true
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
         raw = false
+        strict_option_vars = false
         trusted_config_paths = []
         verbose = true
         yes = true
 
         [status]
         missing_tools = "never"
+        missing_tools_ignore = []
         show_env = false
         show_tools = false
         "###);
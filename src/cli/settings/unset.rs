@@ -50,6 +50,7 @@ mod tests {
         color = true
         disable_default_shorthands = false
         disable_tools = []
+        env_tool_override = []
         experimental = true
         go_default_packages_file = "~/.default-go-packages"
         go_download_mirror = "https://dl.google.com/go"
@@ -57,23 +58,30 @@ mod tests {
         go_set_gopath = false
         go_set_goroot = true
         go_skip_checksum = false
+        hermetic_path = false
+        hermetic_path_base = ["/usr/bin", "/bin"]
+        install_retries = 0
         jobs = 2
         legacy_version_file = true
         legacy_version_file_disable_tools = []
         node_compile = false
         not_found_auto_install = true
+        offline = false
         paranoid = false
         plugin_autoupdate_last_check_duration = "20m"
+        prefer_stable = true
         python_default_packages_file = "~/.default-python-packages"
         python_pyenv_repo = "https://github.com/pyenv/pyenv.git"
         quiet = false
         raw = false
+        strict_option_vars = false
         trusted_config_paths = []
         verbose = true
         yes = true
 
         [status]
         missing_tools = "if_other_versions_installed"
+        missing_tools_ignore = []
         show_env = false
         show_tools = false
         "###);
@@ -5,7 +5,7 @@ use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::env;
 use crate::shell::get_shell;
-use crate::toolset::{InstallOptions, ToolSource, ToolsetBuilder};
+use crate::toolset::{InstallOptions, ToolsetBuilder};
 
 /// Sets a tool version for the current session
 ///
@@ -49,23 +49,22 @@ impl Shell {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: false,
+            pin: true,
+            ..InstallOptions::new()
         };
-        ts.install_arg_versions(&config, &opts)?;
+        let (_, pinned) = ts.install_arg_versions(&config, &opts)?;
         ts.notify_if_versions_missing();
 
         let shell = get_shell(None).expect("no shell detected");
 
-        for (p, tv) in ts.list_current_installed_versions() {
-            let source = &ts.versions.get(p.fa()).unwrap().source;
-            if matches!(source, ToolSource::Argument) {
-                let k = format!("MISE_{}_VERSION", p.id().to_uppercase());
-                let op = if self.unset {
-                    shell.unset_env(&k)
-                } else {
-                    shell.set_env(&k, &tv.version)
-                };
-                miseprintln!("{op}");
-            }
+        for (fa, version) in pinned {
+            let k = format!("MISE_{}_VERSION", fa.id.to_uppercase());
+            let op = if self.unset {
+                shell.unset_env(&k)
+            } else {
+                shell.set_env(&k, &version)
+            };
+            miseprintln!("{op}");
         }
 
         Ok(())
@@ -7,7 +7,7 @@ use eyre::{Context, Result};
 use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::forge::Forge;
-use crate::toolset::{InstallOptions, ToolVersion, ToolsetBuilder};
+use crate::toolset::{InstallOptions, OutdatedInfo, ToolVersion, ToolsetBuilder};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::progress_report::SingleReport;
 use crate::{runtime_symlinks, shims, ui};
@@ -48,14 +48,14 @@ impl Upgrade {
         let mut outdated = ts.list_outdated_versions();
         if self.interactive && !outdated.is_empty() {
             let tvs = self.get_interactive_tool_set(&outdated)?;
-            outdated.retain(|(_, tv, _)| tvs.contains(tv));
+            outdated.retain(|o| tvs.contains(&o.tv));
         } else {
             let tool_set = self
                 .tool
                 .iter()
                 .map(|t| t.forge.clone())
                 .collect::<HashSet<_>>();
-            outdated.retain(|(p, _, _)| tool_set.is_empty() || tool_set.contains(p.fa()));
+            outdated.retain(|o| tool_set.is_empty() || tool_set.contains(o.forge.fa()));
         }
         if outdated.is_empty() {
             info!("All tools are up to date");
@@ -72,17 +72,17 @@ impl Upgrade {
 
         let new_versions = outdated
             .iter()
-            .map(|(_, tv, latest)| {
-                let mut tv = tv.clone();
-                tv.version.clone_from(latest);
+            .map(|o| {
+                let mut tv = o.tv.clone();
+                tv.version.clone_from(&o.latest);
                 tv
             })
             .collect::<Vec<_>>();
 
         let to_remove = outdated
             .into_iter()
-            .filter(|(tool, tv, _)| tool.is_version_installed(tv))
-            .map(|(tool, tv, _)| (tool, tv))
+            .filter(|o| o.forge.is_version_installed(&o.tv))
+            .map(|o| (o.forge, o.tv))
             .collect::<Vec<_>>();
 
         if self.dry_run {
@@ -99,6 +99,7 @@ impl Upgrade {
             jobs: self.jobs,
             raw: self.raw,
             latest_versions: true,
+            ..InstallOptions::new()
         };
         let new_versions = new_versions.into_iter().map(|tv| tv.request).collect();
         ts.install_versions(config, new_versions, &mpr, &opts)?;
@@ -131,19 +132,19 @@ impl Upgrade {
             .description("Select tools to upgrade")
             .filterable(true)
             .min(1);
-        for (_, tv, latest) in outdated {
-            let label = if &tv.version == latest {
-                tv.to_string()
+        for o in outdated {
+            let label = if o.tv.version == o.latest {
+                o.tv.to_string()
             } else {
-                format!("{tv} -> {latest}")
+                format!("{} -> {}", o.tv, o.latest)
             };
-            ms = ms.option(DemandOption::new(tv).label(&label));
+            ms = ms.option(DemandOption::new(&o.tv).label(&label));
         }
         Ok(ms.run()?.into_iter().cloned().collect())
     }
 }
 
-type OutputVec = Vec<(Arc<dyn Forge>, ToolVersion, String)>;
+type OutputVec = Vec<OutdatedInfo>;
 
 #[cfg(test)]
 pub mod tests {
@@ -83,7 +83,7 @@ impl Use {
         let config = Config::try_get()?;
         let mut ts = ToolsetBuilder::new().build(&config)?;
         let mpr = MultiProgressReport::get();
-        let versions: Vec<_> = self
+        let versions: Vec<ToolRequest> = self
             .tool
             .iter()
             .cloned()
@@ -92,6 +92,11 @@ impl Use {
                 None => ToolRequest::new(t.forge, "latest"),
             })
             .collect::<Result<_>>()?;
+        for fa in versions.iter().map(|tr| tr.forge()).unique() {
+            if let Some(setting) = ts.disabled_by(fa) {
+                warn!("{fa} is disabled by {setting} so it will not be activated");
+            }
+        }
         let versions = ts.install_versions(
             &config,
             versions.clone(),
@@ -101,10 +106,12 @@ impl Use {
                 jobs: self.jobs,
                 raw: self.raw,
                 latest_versions: false,
+                ..InstallOptions::new()
             },
         )?;
 
         let mut cf = self.get_config_file()?;
+        let before = cf.to_toolset()?;
         let settings = Settings::try_get()?;
         let pin = self.pin || (settings.asdf_compat && !self.fuzzy);
 
@@ -129,6 +136,11 @@ impl Use {
             cf.remove_plugin(plugin_name)?;
         }
         cf.save()?;
+        debug!(
+            "{}: {}",
+            display_path(cf.get_path()),
+            before.diff(&cf.to_toolset()?)
+        );
         self.render_success_message(cf.as_ref(), &versions)?;
         Ok(())
     }
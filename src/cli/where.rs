@@ -4,7 +4,7 @@ use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::errors::Error::VersionNotInstalled;
 use crate::forge;
-use crate::toolset::ToolsetBuilder;
+use crate::toolset::{resolve_request, ResolveOptions, ToolsetBuilder};
 
 /// Display the installation path for a runtime
 ///
@@ -53,7 +53,7 @@ impl Where {
         match runtime
             .tvr
             .as_ref()
-            .map(|tvr| tvr.resolve(plugin.as_ref(), false))
+            .map(|tvr| resolve_request(tvr, &ResolveOptions::default()))
         {
             Some(Ok(tv)) if plugin.is_version_installed(&tv) => {
                 miseprintln!("{}", tv.install_path().to_string_lossy());
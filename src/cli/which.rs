@@ -1,9 +1,12 @@
+use std::path::Path;
+
 use eyre::Result;
+use itertools::Itertools;
 
 use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::dirs::SHIMS;
-use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::toolset::{ToolVersion, Toolset, ToolsetBuilder, WhichResult};
 
 /// Shows the path that a bin name points to
 #[derive(Debug, clap::Args)]
@@ -25,25 +28,44 @@ pub struct Which {
     /// e.g.: `mise which npm --tool=node@20`
     #[clap(short, long, value_name = "TOOL@VERSION", verbatim_doc_comment)]
     pub tool: Option<ToolArg>,
+
+    /// Fall back to any installed version if no current version provides the bin
+    /// e.g.: node@18 when node@20 is requested but not installed
+    #[clap(long, verbatim_doc_comment)]
+    pub any: bool,
 }
 
 impl Which {
     pub fn run(self) -> Result<()> {
         let ts = self.get_toolset()?;
 
-        match ts.which(&self.bin_name) {
-            Some((p, tv)) => {
-                if self.version {
-                    miseprintln!("{}", tv.version);
-                } else if self.plugin {
-                    miseprintln!("{p}");
-                } else {
-                    let path = p.which(&tv, &self.bin_name)?;
-                    miseprintln!("{}", path.unwrap().display());
-                }
+        let mut result = ts.which_with_install_hint(&self.bin_name);
+        if self.any && !matches!(result, WhichResult::Found(..)) {
+            if let Some((p, tv)) = ts.which_any_installed(&self.bin_name) {
+                result = WhichResult::Found(p, tv);
+            }
+        }
+        if !matches!(result, WhichResult::Found(..)) {
+            // tools requested as "system" are the last resort
+            if let Some((tv, path)) = ts.which_system(&self.bin_name) {
+                self.output(&tv, &path);
+                return Ok(());
+            }
+        }
+        match result {
+            WhichResult::Found(p, tv) => {
+                let path = tv.bin_path(p.as_ref(), &self.bin_name).unwrap();
+                self.output(&tv, &path);
                 Ok(())
             }
-            None => {
+            WhichResult::NotInstalled(tvs) => {
+                let tools = tvs.iter().map(|tv| tv.style()).join(" ");
+                bail!(
+                    "{} is provided by {tools} which is not installed. Install it with `mise install`.",
+                    self.bin_name
+                )
+            }
+            WhichResult::NotFound => {
                 if self.has_shim(&self.bin_name) {
                     bail!("{} is a mise bin however it is not currently active. Use `mise use` to activate it in this directory.", self.bin_name)
                 } else {
@@ -55,6 +77,15 @@ impl Which {
             }
         }
     }
+    fn output(&self, tv: &ToolVersion, path: &Path) {
+        if self.version {
+            miseprintln!("{}", tv.version);
+        } else if self.plugin {
+            miseprintln!("{}", tv.forge);
+        } else {
+            miseprintln!("{}", path.display());
+        }
+    }
     fn get_toolset(&self) -> Result<Toolset> {
         let config = Config::try_get()?;
         let mut tsb = ToolsetBuilder::new();
@@ -1,11 +1,13 @@
 use std::ffi::{OsStr, OsString};
 use std::fmt::{Display, Formatter};
 use std::io::{BufRead, BufReader, Write};
+use std::os::unix::process::CommandExt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
-use std::sync::mpsc::channel;
+use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::sync::{Mutex, RwLock};
 use std::thread;
+use std::time::{Duration, Instant};
 
 use color_eyre::Result;
 use duct::{Expression, IntoExecutablePath};
@@ -15,7 +17,7 @@ use signal_hook::iterator::Signals;
 
 use crate::config::{Config, Settings};
 use crate::env;
-use crate::errors::Error::ScriptFailed;
+use crate::errors::Error::{ScriptFailed, TimedOut};
 use crate::file::display_path;
 use crate::forge::Forge;
 use crate::toolset::{ToolRequest, ToolsetBuilder};
@@ -120,7 +122,7 @@ where
         .with_tool_filter(dependencies)
         .with_installed_only()
         .build(&config)?
-        .full_env()?;
+        .full_env(&config)?;
     Ok(cmd(program, args).full_env(env))
 }
 
@@ -131,6 +133,7 @@ pub struct CmdLineRunner<'a> {
     prefix: String,
     raw: bool,
     pass_signals: bool,
+    deadline: Option<Instant>,
 }
 
 static OUTPUT_LOCK: Mutex<()> = Mutex::new(());
@@ -149,6 +152,7 @@ impl<'a> CmdLineRunner<'a> {
             prefix: String::new(),
             raw: false,
             pass_signals: false,
+            deadline: None,
         }
     }
 
@@ -250,6 +254,17 @@ impl<'a> CmdLineRunner<'a> {
         self
     }
 
+    /// the command is sent SIGTERM once the deadline passes, and SIGKILL if it is still running
+    /// 5s later, execute then returns Error::TimedOut after it has exited
+    /// it runs in its own process group so the signals reach everything it started as well
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        if deadline.is_some() {
+            self.cmd.process_group(0);
+        }
+        self.deadline = deadline;
+        self
+    }
+
     pub fn stdin_string(mut self, input: impl Into<String>) -> Self {
         self.cmd.stdin(Stdio::piped());
         self.stdin = Some(input.into());
@@ -322,7 +337,28 @@ impl<'a> CmdLineRunner<'a> {
         });
         let mut combined_output = vec![];
         let mut status = None;
-        for line in rx {
+        let mut kill_at = self.deadline.map(|deadline| (deadline, "TERM"));
+        let mut killed = false;
+        loop {
+            let line = match kill_at {
+                Some((at, _)) => rx.recv_timeout(at.saturating_duration_since(Instant::now())),
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            let line = match line {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => {
+                    let (_, sig) = kill_at.unwrap();
+                    debug!("{self} ran past its deadline, sending SIG{sig}");
+                    kill_process_group(id, sig)?;
+                    killed = true;
+                    kill_at = match sig {
+                        "TERM" => Some((Instant::now() + Duration::from_secs(5), "KILL")),
+                        _ => None,
+                    };
+                    continue;
+                }
+            };
             match line {
                 ChildProcessOutput::Stdout(line) => {
                     self.on_stdout(&line);
@@ -334,9 +370,15 @@ impl<'a> CmdLineRunner<'a> {
                 }
                 ChildProcessOutput::ExitStatus(s) => {
                     status = Some(s);
+                    // the output stays open while its children are alive, the command itself
+                    // has stopped though
+                    if killed {
+                        break;
+                    }
                 }
                 ChildProcessOutput::Signal(sig) => {
-                    if sig != SIGINT {
+                    // in its own process group the command doesn't get the terminal's SIGINT
+                    if sig != SIGINT || self.deadline.is_some() {
                         cmd!("kill", format!("-{sig}"), id.to_string()).run()?;
                     }
                 }
@@ -344,6 +386,9 @@ impl<'a> CmdLineRunner<'a> {
         }
         let status = status.unwrap();
 
+        if killed && !status.success() {
+            return Err(TimedOut(self.to_string()).into());
+        }
         if !status.success() {
             self.on_error(combined_output.join("\n"), status)?;
         }
@@ -352,7 +397,22 @@ impl<'a> CmdLineRunner<'a> {
     }
 
     fn execute_raw(mut self) -> Result<()> {
-        let status = self.cmd.spawn()?.wait()?;
+        let mut cp = self.cmd.spawn()?;
+        let status = match self.deadline {
+            Some(deadline) => loop {
+                if let Some(status) = cp.try_wait()? {
+                    break status;
+                }
+                if Instant::now() >= deadline {
+                    debug!("{self} ran past its deadline, killing it");
+                    kill_process_group(cp.id(), "KILL")?;
+                    cp.wait()?;
+                    return Err(TimedOut(self.to_string()).into());
+                }
+                thread::sleep(Duration::from_millis(100));
+            },
+            None => cp.wait()?,
+        };
         match status.success() {
             true => Ok(()),
             false => self.on_error(String::new(), status),
@@ -425,6 +485,14 @@ impl Display for CmdLineRunner<'_> {
     }
 }
 
+/// signals every process in the group of a command run with CmdLineRunner::with_deadline
+fn kill_process_group(pgid: u32, sig: &str) -> Result<()> {
+    cmd!("kill", format!("-{sig}"), "--", format!("-{pgid}"))
+        .unchecked()
+        .run()?;
+    Ok(())
+}
+
 enum ChildProcessOutput {
     Stdout(String),
     Stderr(String),
@@ -434,11 +502,35 @@ enum ChildProcessOutput {
 
 #[cfg(test)]
 mod tests {
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+
     use crate::cmd;
+    use crate::cmd::CmdLineRunner;
+    use crate::errors::Error;
 
     #[test]
     fn test_cmd() {
         let output = cmd!("echo", "foo", "bar").read().unwrap();
         assert_eq!("foo bar", output);
     }
+
+    #[test]
+    fn test_deadline_kills_process_group() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("marker");
+        let err = CmdLineRunner::new("sh")
+            .arg("-c")
+            .arg(format!("(sleep 1 && touch {}) & wait", marker.display()))
+            .with_deadline(Some(Instant::now() + Duration::from_millis(200)))
+            .execute()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::TimedOut(_))
+        ));
+        // the background job was killed along with the shell
+        sleep(Duration::from_secs(2));
+        assert!(!marker.exists());
+    }
 }
@@ -116,7 +116,8 @@ impl dyn ConfigFile {
                 .map(|tvr| {
                     if pin {
                         let plugin = forge::get(&fa);
-                        let tv = tvr.resolve(plugin.as_ref(), false)?;
+                        let prefer_stable = Settings::get().prefer_stable;
+                        let tv = tvr.resolve(plugin.as_ref(), false, prefer_stable)?;
                         Ok(tv.version)
                     } else {
                         Ok(tvr.version())
@@ -185,6 +185,20 @@ impl Config {
         Ok(v.to_string())
     }
 
+    /// names of the aliases resolve_alias knows for a tool, from the config and the forge
+    pub fn alias_names(&self, forge: &dyn Forge) -> Result<Vec<String>> {
+        let names = self
+            .aliases
+            .get(forge.fa())
+            .into_iter()
+            .flat_map(|aliases| aliases.keys().cloned())
+            .chain(forge.get_aliases()?.into_keys())
+            .sorted()
+            .dedup()
+            .collect();
+        Ok(names)
+    }
+
     fn load_all_aliases(&self) -> AliasMap {
         let mut aliases: AliasMap = self.aliases.clone();
         let plugin_aliases: Vec<_> = forge::list()
@@ -1,8 +1,9 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::iter::once;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 #[allow(unused_imports)]
 use confique::env::parse::{list_by_colon, list_by_comma};
@@ -14,6 +15,8 @@ use serde_derive::{Deserialize, Serialize};
 
 use crate::config::{system_config_files, DEFAULT_CONFIG_FILENAMES};
 use crate::file::FindUp;
+use crate::forge::ForgeType;
+use crate::toolset::ToolVersionOptions;
 use crate::{config, dirs, env, file};
 
 #[rustfmt::skip]
@@ -44,8 +47,16 @@ pub struct Settings {
     pub disable_default_shorthands: bool,
     #[config(env = "MISE_DISABLE_TOOLS", default = [], parse_env = list_by_comma)]
     pub disable_tools: BTreeSet<String>,
+    /// env vars which tools can set even if they're in the config's env, e.g.: GOROOT
+    #[config(env = "MISE_ENV_TOOL_OVERRIDE", default = [], parse_env = list_by_comma)]
+    pub env_tool_override: BTreeSet<String>,
     #[config(env = "MISE_EXPERIMENTAL", default = false)]
     pub experimental: bool,
+    /// options added to every request of a forge type, options set on a request take precedence
+    /// e.g.: { cargo = { locked = "true" } }
+    pub forge_default_options: Option<BTreeMap<String, BTreeMap<String, String>>>,
+    /// max number of tools of a forge type to install in parallel, e.g.: { npm = 1 }
+    pub forge_jobs: Option<BTreeMap<String, usize>>,
     /// after installing a go version, run `go install` on packages listed in this file
     #[config(env = "MISE_GO_DEFAULT_PACKAGES_FILE", default = "~/.default-go-packages")]
     pub go_default_packages_file: PathBuf,
@@ -74,20 +85,46 @@ pub struct Settings {
     /// set to true to skip checksum verification when downloading go sdk tarballs
     #[config(env = "MISE_GO_SKIP_CHECKSUM", default = false)]
     pub go_skip_checksum: bool,
+    /// build PATH from hermetic_path_base and the tools' bin paths instead of the inherited PATH
+    #[config(env = "MISE_HERMETIC_PATH", default = false)]
+    pub hermetic_path: bool,
+    /// the dirs PATH starts from when hermetic_path is set
+    #[config(env = "MISE_HERMETIC_PATH_BASE", default = ["/usr/bin", "/bin"], parse_env = list_by_colon)]
+    pub hermetic_path_base: Vec<PathBuf>,
+    /// number of times to retry a failed install before giving up
+    #[config(env = "MISE_INSTALL_RETRIES", default = 0)]
+    pub install_retries: usize,
+    /// abort installing a single tool version if it takes longer than this, e.g.: "10m"
+    #[config(env = "MISE_INSTALL_TIMEOUT")]
+    pub install_timeout: Option<String>,
     #[config(env = "MISE_JOBS", default = 4)]
     pub jobs: usize,
     #[config(env = "MISE_LEGACY_VERSION_FILE", default = true)]
     pub legacy_version_file: bool,
     #[config(env = "MISE_LEGACY_VERSION_FILE_DISABLE_TOOLS", default = [], parse_env = list_by_comma)]
     pub legacy_version_file_disable_tools: BTreeSet<String>,
+    /// fail an install once the tools it downloads add up to more than this many bytes
+    #[config(env = "MISE_MAX_DOWNLOAD_SIZE")]
+    pub max_download_size: Option<u64>,
     #[config(env = "MISE_NODE_COMPILE", default = false)]
     pub node_compile: bool,
     #[config(env = "MISE_NOT_FOUND_AUTO_INSTALL", default = true)]
     pub not_found_auto_install: bool,
+    /// never touch the network when installing, versions are resolved from the cached remote
+    /// versions and forges only use already-downloaded artifacts
+    #[config(env = "MISE_OFFLINE", default = false)]
+    pub offline: bool,
     #[config(env = "MISE_PARANOID", default = false)]
     pub paranoid: bool,
     #[config(env = "MISE_PLUGIN_AUTOUPDATE_LAST_CHECK_DURATION", default = "7d")]
     pub plugin_autoupdate_last_check_duration: String,
+    /// command to run after installing a tool, keyed by tool, e.g.: { node = "./patch.sh" }
+    pub post_install: Option<BTreeMap<String, String>>,
+    /// command to run before installing a tool, keyed by tool
+    pub pre_install: Option<BTreeMap<String, String>>,
+    /// skip prereleases like "2.0.0-rc1" when resolving versions unless there is no stable one
+    #[config(env = "MISE_PREFER_STABLE", default = true)]
+    pub prefer_stable: bool,
     #[config(env = "MISE_PYTHON_COMPILE")]
     pub python_compile: Option<bool>,
     #[config(env = "MISE_PYTHON_DEFAULT_PACKAGES_FILE")]
@@ -106,6 +143,9 @@ pub struct Settings {
     pub raw: bool,
     #[config(env = "MISE_SHORTHANDS_FILE")]
     pub shorthands_file: Option<PathBuf>,
+    /// error on undefined variables in tool options instead of expanding them to an empty string
+    #[config(env = "MISE_STRICT_OPTION_VARS", default = false)]
+    pub strict_option_vars: bool,
     /// what level of status messages to display when entering directories
     #[config(nested)]
     pub status: SettingsStatus,
@@ -147,6 +187,13 @@ pub struct SettingsStatus {
         default = "if_other_versions_installed"
     )]
     pub missing_tools: SettingsStatusMissingTools,
+    /// forge ids which are never warned about if missing, e.g.: ["npm:*", "python"]
+    #[config(
+        env = "MISE_STATUS_MESSAGE_MISSING_TOOLS_IGNORE",
+        default = [],
+        parse_env = list_by_comma
+    )]
+    pub missing_tools_ignore: Vec<String>,
     /// show env var keys when entering directories
     #[config(env = "MISE_STATUS_MESSAGE_SHOW_ENV", default = false)]
     pub show_env: bool,
@@ -404,6 +451,29 @@ impl Settings {
             })
     }
 
+    pub fn forge_default_options(&self) -> Result<BTreeMap<ForgeType, ToolVersionOptions>> {
+        self.forge_default_options
+            .iter()
+            .flatten()
+            .map(|(forge_type, options)| Ok((forge_type.parse()?, options.clone())))
+            .collect()
+    }
+
+    pub fn forge_jobs(&self) -> Result<BTreeMap<ForgeType, usize>> {
+        self.forge_jobs
+            .iter()
+            .flatten()
+            .map(|(forge_type, jobs)| Ok((forge_type.parse()?, *jobs)))
+            .collect()
+    }
+
+    pub fn install_timeout(&self) -> Result<Option<Duration>> {
+        self.install_timeout
+            .as_ref()
+            .map(|t| Ok(t.parse::<humantime::Duration>()?.into()))
+            .transpose()
+    }
+
     pub fn env_files(&self) -> Vec<PathBuf> {
         let mut files = vec![];
         if let Some(cwd) = &*dirs::CWD {
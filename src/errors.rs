@@ -1,7 +1,11 @@
 use std::process::ExitStatus;
 
+use itertools::Itertools;
 use thiserror::Error;
 
+use crate::cli::args::ForgeArg;
+use crate::toolset::{ToolRequest, ToolVersion};
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("[{0}] plugin not installed")]
@@ -10,8 +14,65 @@ pub enum Error {
     VersionNotInstalled(String, String),
     #[error("{} exited with non-zero status: {}", .0, render_exit_status(.1))]
     ScriptFailed(String, Option<ExitStatus>),
+    /// a command or install was stopped at its deadline, see InstallContext::deadline
+    #[error("{0} timed out")]
+    TimedOut(String),
     #[error("Config file is not trusted.\nTrust it with `mise trust`.")]
     UntrustedConfig(),
+    #[error("error resolving versions\n{}", render_resolve_errors(.0))]
+    ResolveFailed(Vec<(ForgeArg, ResolveError)>),
+    #[error("{}", render_install_failures(.installed, .failed))]
+    InstallFailed {
+        installed: Vec<ToolVersion>,
+        failed: Vec<(ToolRequest, eyre::Report)>,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum ResolveError {
+    #[error("[{0}] plugin not installed")]
+    PluginMissing(ForgeArg),
+    #[error("network error: {0:#}")]
+    Network(eyre::Report),
+    #[error("{0:#}")]
+    VersionNotFound(eyre::Report),
+}
+
+impl ResolveError {
+    pub fn new(fa: &ForgeArg, is_installed: bool, err: eyre::Report) -> Self {
+        let plugin_missing = err
+            .chain()
+            .any(|e| matches!(e.downcast_ref(), Some(Error::PluginNotInstalled(_))));
+        if plugin_missing || !is_installed {
+            ResolveError::PluginMissing(fa.clone())
+        } else if err.chain().any(|e| e.is::<reqwest::Error>()) {
+            ResolveError::Network(err)
+        } else {
+            ResolveError::VersionNotFound(err)
+        }
+    }
+}
+
+fn render_install_failures(
+    installed: &[ToolVersion],
+    failed: &[(ToolRequest, eyre::Report)],
+) -> String {
+    let errors = failed
+        .iter()
+        .map(|(tr, err)| format!("{tr}: {err:#}"))
+        .join("\n");
+    format!(
+        "failed to install {} tools ({} installed)\n{errors}",
+        failed.len(),
+        installed.len()
+    )
+}
+
+fn render_resolve_errors(errors: &[(ForgeArg, ResolveError)]) -> String {
+    errors
+        .iter()
+        .map(|(fa, err)| format!("{fa}: {err}"))
+        .join("\n")
 }
 
 fn render_exit_status(exit_status: &Option<ExitStatus>) -> String {
@@ -257,6 +257,12 @@ pub fn make_executable(path: &Path) -> Result<()> {
     Ok(())
 }
 
+pub fn chmod(path: &Path, mode: u32) -> Result<()> {
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .wrap_err_with(|| format!("failed to chmod {mode:o}: {}", display_path(path)))?;
+    Ok(())
+}
+
 pub fn all_dirs() -> Result<Vec<PathBuf>> {
     let mut output = vec![];
     let mut cwd = dirs::CWD.as_ref().map(|p| p.as_path());
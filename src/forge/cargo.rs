@@ -54,7 +54,8 @@ impl Forge for CargoForge {
         let config = Config::try_get()?;
         let settings = Settings::get();
         settings.ensure_experimental("cargo backend")?;
-        let cmd = if self.is_binstall_enabled() {
+        ctx.ensure_online(format!("{}@{} from crates.io", self.name(), ctx.tv.version))?;
+        let mut cmd = if self.is_binstall_enabled() {
             let mut runner = CmdLineRunner::new("cargo-binstall").arg("-y");
             if let Some(token) = &*GITHUB_TOKEN {
                 runner = runner.env("GITHUB_TOKEN", token)
@@ -63,11 +64,15 @@ impl Forge for CargoForge {
         } else {
             CmdLineRunner::new("cargo").arg("install")
         };
+        if let Some(index) = ctx.tv.source_url() {
+            cmd = cmd.arg("--index").arg(index);
+        }
 
         cmd.arg(&format!("{}@{}", self.name(), ctx.tv.version))
             .arg("--root")
             .arg(ctx.tv.install_path())
             .with_pr(ctx.pr.as_ref())
+            .with_deadline(ctx.deadline)
             .envs(ctx.ts.env_with_path(&config)?)
             .prepend_path(ctx.ts.list_paths())?
             .execute()?;
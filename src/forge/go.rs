@@ -61,6 +61,11 @@ impl Forge for GoForge {
         let config = Config::try_get()?;
         let settings = Settings::get();
         settings.ensure_experimental("go backend")?;
+        ctx.ensure_online(format!(
+            "{}@{} from the go proxy",
+            self.name(),
+            ctx.tv.version
+        ))?;
 
         // if the (semantic) version has no v prefix, add it
         // we allow max. 6 digits for the major version to prevent clashes with Git commit hashes
@@ -74,6 +79,7 @@ impl Forge for GoForge {
             .arg("install")
             .arg(&format!("{}@{}", self.name(), version))
             .with_pr(ctx.pr.as_ref())
+            .with_deadline(ctx.deadline)
             .envs(ctx.ts.env_with_path(&config)?)
             .env("GOBIN", ctx.tv.install_path().join("bin"))
             .execute()?;
@@ -0,0 +1,168 @@
+use std::env::consts::{ARCH, OS};
+use std::fmt::Debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::WrapErr;
+
+use crate::cli::args::ForgeArg;
+use crate::config::Settings;
+use crate::file;
+use crate::file::display_path;
+use crate::forge::{Forge, ForgeType};
+use crate::http::HTTP_FETCH;
+use crate::install_context::InstallContext;
+use crate::toolset::{ToolRequest, ToolVersion};
+
+/// Installs a tool by downloading and extracting an archive from a url.
+/// The url is the `url` option (or the tool name) and may contain `{version}`, `{os}`, and `{arch}`
+/// e.g.: `"http:mytool" = { version = "1.0.0", url = "https://example.com/mytool-{version}-{os}-{arch}.tar.gz" }`
+#[derive(Debug)]
+pub struct HttpForge {
+    fa: ForgeArg,
+}
+
+impl Forge for HttpForge {
+    fn get_type(&self) -> ForgeType {
+        ForgeType::Http
+    }
+
+    fn fa(&self) -> &ForgeArg {
+        &self.fa
+    }
+
+    fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
+        // a url can't be queried for versions so they need to be specified explicitly
+        Ok(vec![])
+    }
+
+    fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let settings = Settings::get();
+        settings.ensure_experimental("http backend")?;
+        let url = self.url(&ctx.tv);
+        let filename = url.rsplit('/').next().unwrap_or_default();
+        let filename = filename.split(['?', '#']).next().unwrap_or_default();
+        let archive = ctx.tv.download_path().join(filename);
+
+        if !archive.exists() {
+            ctx.pr.set_message(format!("downloading {url}"));
+            ctx.download_file(&HTTP_FETCH, &url, &archive)?;
+            ctx.add_download(archive.metadata()?.len())?;
+        }
+
+        if let Some(checksum) = ctx.tv.checksum() {
+            ctx.pr.set_message(format!("verifying {filename}"));
+            checksum.verify(&archive, Some(ctx.pr.as_ref()))?;
+        }
+
+        ctx.pr.set_message(format!("extracting {filename}"));
+        let install_path = ctx.tv.install_path();
+        if filename.ends_with(".zip") {
+            file::unzip(&archive, &install_path)?;
+        } else if filename.ends_with(".tar.gz") || filename.ends_with(".tgz") {
+            file::untar(&archive, &install_path)?;
+        } else {
+            bail!("unsupported archive {filename}, expected .tar.gz, .tgz, or .zip")
+        }
+        post_extract(&ctx.tv, &find_bin_dir(&install_path))
+    }
+
+    fn list_bin_paths(&self, tv: &ToolVersion) -> eyre::Result<Vec<PathBuf>> {
+        match tv.request {
+            ToolRequest::System(_) => Ok(vec![]),
+            _ => Ok(vec![find_bin_dir(&tv.install_short_path())]),
+        }
+    }
+}
+
+impl HttpForge {
+    pub fn new(name: String) -> Self {
+        Self {
+            fa: ForgeArg::new(ForgeType::Http, &name),
+        }
+    }
+
+    fn url(&self, tv: &ToolVersion) -> String {
+        let options = tv.request.options();
+        let url = options
+            .get("url")
+            .map(|s| s.as_str())
+            .unwrap_or(self.name());
+        url.replace("{version}", &tv.version)
+            .replace("{os}", OS)
+            .replace("{arch}", ARCH)
+    }
+}
+
+/// applies the `chmod` and `strip` options to the bins once extracted, e.g.:
+/// `"http:mytool" = { version = "1.0.0", url = "...", chmod = "755", strip = "true" }`
+fn post_extract(tv: &ToolVersion, bin_dir: &Path) -> eyre::Result<()> {
+    let options = tv.request.options();
+    let bins = file::ls(bin_dir)?;
+    if let Some(mode) = options.get("chmod") {
+        let mode = u32::from_str_radix(mode, 8)
+            .wrap_err_with(|| format!("invalid chmod option {mode} of {tv}, expected e.g.: 755"))?;
+        for bin in &bins {
+            file::chmod(bin, mode)?;
+        }
+    }
+    if options.get("strip").is_some_and(|s| s == "true") {
+        let Some(strip) = file::which("strip") else {
+            warn!("strip is not installed, {tv} is not stripped");
+            return Ok(());
+        };
+        for bin in &bins {
+            if let Err(err) = cmd!(&strip, bin).run() {
+                warn!("failed to strip {}: {err:#}", display_path(bin));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// archives usually have a bin dir, sometimes nested in a single top-level directory
+/// like "mytool-1.0.0/bin", otherwise the bins are expected in the top-level directory
+fn find_bin_dir(dir: &Path) -> PathBuf {
+    if dir.join("bin").is_dir() {
+        return dir.join("bin");
+    }
+    let entries = fs::read_dir(dir)
+        .map(|rd| {
+            rd.filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    match entries.as_slice() {
+        [subdir] if subdir.is_dir() => find_bin_dir(subdir),
+        _ => dir.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use crate::dirs;
+
+    use super::*;
+
+    #[test]
+    fn test_post_extract() {
+        let forge = HttpForge::new("mytool".into());
+        let bin_dir = dirs::CACHE.join("test-post-extract");
+        let bin = bin_dir.join("mytool");
+        file::create_dir_all(&bin_dir).unwrap();
+        file::write(&bin, "#!/bin/sh\necho mytool\n").unwrap();
+        file::chmod(&bin, 0o644).unwrap();
+        let options = [("chmod", "755"), ("strip", "true")]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .into();
+        let tr = ToolRequest::new_opts(forge.fa().clone(), "1.0.0", options).unwrap();
+        let tv = ToolVersion::new(&forge, tr, "1.0.0".into());
+        // strip fails on a script which is only a warning
+        post_extract(&tv, &bin_dir).unwrap();
+        assert_eq!(bin.metadata().unwrap().permissions().mode() & 0o777, 0o755);
+        file::remove_all(&bin_dir).unwrap();
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use eyre::Result;
+
+use crate::cli::args::ForgeArg;
+use crate::config::Config;
+use crate::forge::Forge;
+use crate::install_context::InstallContext;
+use crate::toolset::{ToolRequest, ToolVersion, Toolset};
+
+type InstallFn = Box<dyn Fn(&InstallContext) -> Result<()> + Send + Sync>;
+
+/// a configurable forge for tests, register it with forge::insert if the toolset looks it up
+/// anything which isn't configured behaves like the default Forge methods, the calls which hit
+/// the network or the disk are counted so tests can check what was cached
+pub struct MockForge {
+    fa: ForgeArg,
+    remote_versions: Vec<String>,
+    aliases: BTreeMap<String, String>,
+    dependencies: Vec<ForgeArg>,
+    installed_versions: Option<Vec<String>>,
+    bins: Vec<String>,
+    exec_env: BTreeMap<String, String>,
+    install: InstallFn,
+    pub remote_calls: AtomicUsize,
+    pub installed_calls: AtomicUsize,
+    pub which_calls: AtomicUsize,
+    pub exec_env_calls: AtomicUsize,
+}
+
+impl MockForge {
+    pub fn new(id: &str) -> Self {
+        Self {
+            fa: id.into(),
+            remote_versions: vec![],
+            aliases: BTreeMap::new(),
+            dependencies: vec![],
+            installed_versions: None,
+            bins: vec![],
+            exec_env: BTreeMap::new(),
+            install: Box::new(|_| Ok(())),
+            remote_calls: AtomicUsize::new(0),
+            installed_calls: AtomicUsize::new(0),
+            which_calls: AtomicUsize::new(0),
+            exec_env_calls: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn with_remote_versions(mut self, versions: &[&str]) -> Self {
+        self.remote_versions = versions.iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    pub fn with_aliases(mut self, aliases: &[(&str, &str)]) -> Self {
+        self.aliases = aliases
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self
+    }
+
+    pub fn with_dependencies(mut self, dependencies: &[&str]) -> Self {
+        self.dependencies = dependencies.iter().map(|&d| d.into()).collect();
+        self
+    }
+
+    /// these versions count as installed without anything in the installs dir
+    pub fn with_installed_versions(mut self, versions: &[&str]) -> Self {
+        self.installed_versions = Some(versions.iter().map(|v| v.to_string()).collect());
+        self
+    }
+
+    /// which finds these bins in the bin dir of every version
+    pub fn with_bins(mut self, bins: &[&str]) -> Self {
+        self.bins = bins.iter().map(|b| b.to_string()).collect();
+        self
+    }
+
+    pub fn with_exec_env(mut self, env: &[(&str, &str)]) -> Self {
+        self.exec_env = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self
+    }
+
+    /// runs instead of installing anything, installs succeed by default
+    pub fn with_install(
+        mut self,
+        install: impl Fn(&InstallContext) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.install = Box::new(install);
+        self
+    }
+
+    /// the default Forge methods, used for whatever isn't configured
+    fn defaults(&self) -> Defaults {
+        Defaults(self.fa.clone())
+    }
+}
+
+impl Debug for MockForge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MockForge").field(&self.fa).finish()
+    }
+}
+
+impl Forge for MockForge {
+    fn fa(&self) -> &ForgeArg {
+        &self.fa
+    }
+    fn get_dependencies(&self, _tvr: &ToolRequest) -> Result<Vec<ForgeArg>> {
+        Ok(self.dependencies.clone())
+    }
+    fn _list_remote_versions(&self) -> Result<Vec<String>> {
+        self.remote_calls.fetch_add(1, Ordering::Relaxed);
+        Ok(self.remote_versions.clone())
+    }
+    fn get_aliases(&self) -> Result<BTreeMap<String, String>> {
+        Ok(self.aliases.clone())
+    }
+    fn list_installed_versions(&self) -> Result<Vec<String>> {
+        self.installed_calls.fetch_add(1, Ordering::Relaxed);
+        match &self.installed_versions {
+            Some(versions) => Ok(versions.clone()),
+            None => self.defaults().list_installed_versions(),
+        }
+    }
+    fn is_version_installed(&self, tv: &ToolVersion) -> bool {
+        match &self.installed_versions {
+            Some(versions) => versions.contains(&tv.version),
+            None => self.defaults().is_version_installed(tv),
+        }
+    }
+    fn which(&self, tv: &ToolVersion, bin_name: &str) -> Result<Option<PathBuf>> {
+        self.which_calls.fetch_add(1, Ordering::Relaxed);
+        if self.bins.is_empty() {
+            return self.defaults().which(tv, bin_name);
+        }
+        let bin_path = tv.install_short_path().join("bin");
+        Ok(self
+            .bins
+            .iter()
+            .any(|bin| bin == bin_name)
+            .then(|| bin_path.join(bin_name)))
+    }
+    fn exec_env(
+        &self,
+        _config: &Config,
+        _ts: &Toolset,
+        _tv: &ToolVersion,
+    ) -> Result<BTreeMap<String, String>> {
+        self.exec_env_calls.fetch_add(1, Ordering::Relaxed);
+        Ok(self.exec_env.clone())
+    }
+    fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
+        (self.install)(ctx)
+    }
+}
+
+/// a forge with nothing but the default Forge methods
+#[derive(Debug)]
+struct Defaults(ForgeArg);
+
+impl Forge for Defaults {
+    fn fa(&self) -> &ForgeArg {
+        &self.0
+    }
+    fn _list_remote_versions(&self) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+    fn install_version_impl(&self, _ctx: &InstallContext) -> Result<()> {
+        Ok(())
+    }
+}
@@ -15,6 +15,7 @@ use strum::IntoEnumIterator;
 use versions::Versioning;
 
 use crate::cli::args::ForgeArg;
+use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::file::{display_path, remove_all, remove_all_with_warning};
 use crate::forge::cargo::CargoForge;
@@ -23,16 +24,21 @@ use crate::lock_file::LockFile;
 use crate::plugins::core::CORE_PLUGINS;
 use crate::plugins::{ExternalPlugin, PluginType, VERSION_REGEX};
 use crate::runtime_symlinks::is_runtime_symlink;
-use crate::toolset::{ToolRequest, ToolVersion, Toolset, ToolsetBuilder};
+use crate::toolset::{
+    find_bin, ToolRequest, ToolVersion, Toolset, ToolsetBuilder, INSTALLED_VERSIONS,
+};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::progress_report::SingleReport;
-use crate::{dirs, file};
+use crate::{dirs, file, registry};
 
 use self::forge_meta::ForgeMeta;
 
 mod cargo;
 pub mod forge_meta;
 mod go;
+mod http;
+#[cfg(test)]
+pub mod mock;
 mod npm;
 mod pipx;
 mod ubi;
@@ -49,11 +55,31 @@ pub enum ForgeType {
     Asdf,
     Cargo,
     Go,
+    Http,
     Npm,
     Pipx,
     Ubi,
 }
 
+impl ForgeType {
+    /// well-known tools of this backend which can be used by their short name, e.g.: `mise use
+    /// ripgrep`, these are added to the registry, see registry::REGISTRY
+    pub fn builtin_aliases(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Cargo => &[
+                ("hyperfine", "cargo:hyperfine"),
+                ("ripgrep", "cargo:ripgrep"),
+            ],
+            Self::Npm => &[
+                ("prettier", "npm:prettier"),
+                ("typescript", "npm:typescript"),
+            ],
+            Self::Pipx => &[("black", "pipx:black")],
+            Self::Asdf | Self::Go | Self::Http | Self::Ubi => &[],
+        }
+    }
+}
+
 impl Display for ForgeType {
     fn fmt(&self, formatter: &mut Formatter) -> std::fmt::Result {
         write!(formatter, "{}", format!("{:?}", self).to_lowercase())
@@ -91,6 +117,7 @@ fn list_installed_forges() -> eyre::Result<ForgeList> {
                 ForgeType::Cargo => Arc::new(CargoForge::new(fa.name)) as AForge,
                 ForgeType::Npm => Arc::new(npm::NPMForge::new(fa.name)) as AForge,
                 ForgeType::Go => Arc::new(go::GoForge::new(fa.name)) as AForge,
+                ForgeType::Http => Arc::new(http::HttpForge::new(fa.name)) as AForge,
                 ForgeType::Pipx => Arc::new(pipx::PIPXForge::new(fa.name)) as AForge,
                 ForgeType::Ubi => Arc::new(ubi::UbiForge::new(fa.name)) as AForge,
             }
@@ -108,23 +135,35 @@ pub fn list_forge_types() -> Vec<ForgeType> {
 }
 
 pub fn get(fa: &ForgeArg) -> AForge {
-    if let Some(forge) = load_forges().get(fa) {
+    // a plugin which is not installed yet is recreated if it was loaded without the source url
+    let is_current = |forge: &AForge| {
+        fa.source_url.is_none() || forge.is_installed() || forge.fa().source_url == fa.source_url
+    };
+    if let Some(forge) = load_forges().get(fa).filter(|f| is_current(f)) {
         forge.clone()
     } else {
         let mut m = FORGES.lock().unwrap();
         let forges = m.as_mut().unwrap();
+        if let Some(forge) = forges.get(fa).filter(|f| is_current(f)) {
+            return forge.clone();
+        }
         let name = fa.name.to_string();
-        forges
-            .entry(fa.clone())
-            .or_insert_with(|| match fa.forge_type {
-                ForgeType::Asdf => Arc::new(ExternalPlugin::new(name)),
-                ForgeType::Cargo => Arc::new(CargoForge::new(name)),
-                ForgeType::Npm => Arc::new(npm::NPMForge::new(name)),
-                ForgeType::Go => Arc::new(go::GoForge::new(name)),
-                ForgeType::Pipx => Arc::new(pipx::PIPXForge::new(name)),
-                ForgeType::Ubi => Arc::new(ubi::UbiForge::new(name)),
-            })
-            .clone()
+        let forge: AForge = match fa.forge_type {
+            ForgeType::Asdf => {
+                let mut plugin = ExternalPlugin::new(name);
+                plugin.fa.source_url.clone_from(&fa.source_url);
+                plugin.repo_url.clone_from(&fa.source_url);
+                Arc::new(plugin)
+            }
+            ForgeType::Cargo => Arc::new(CargoForge::new(name)),
+            ForgeType::Npm => Arc::new(npm::NPMForge::new(name)),
+            ForgeType::Go => Arc::new(go::GoForge::new(name)),
+            ForgeType::Http => Arc::new(http::HttpForge::new(name)),
+            ForgeType::Pipx => Arc::new(pipx::PIPXForge::new(name)),
+            ForgeType::Ubi => Arc::new(ubi::UbiForge::new(name)),
+        };
+        forges.insert(fa.clone(), forge.clone());
+        forge
     }
 }
 
@@ -274,7 +313,7 @@ pub trait Forge: Debug + Send + Sync {
         let ts = ToolsetBuilder::new()
             .with_tool_filter(deps.clone())
             .build(&config)?;
-        if !ts.list_missing_versions().is_empty() {
+        if !ts.is_fully_installed() {
             bail!(
                 "Dependency {} not installed for {}",
                 deps.iter().map(|d| d.to_string()).join(", "),
@@ -313,7 +352,7 @@ pub trait Forge: Debug + Send + Sync {
     }
 
     #[requires(ctx.tv.forge.forge_type == self.get_type())]
-    fn install_version(&self, ctx: InstallContext) -> eyre::Result<()> {
+    fn install_version(&self, ctx: &InstallContext) -> eyre::Result<()> {
         ensure!(self.is_installed(), "{} is not installed", self.id());
         let config = Config::get();
         let settings = Settings::try_get()?;
@@ -326,14 +365,27 @@ pub trait Forge: Debug + Send + Sync {
             }
         }
         let _lock = self.get_lock(&ctx.tv.install_path(), ctx.force)?;
-        self.create_install_dirs(&ctx.tv)?;
+        if let Some(hook) = settings.pre_install.as_ref().and_then(|h| h.get(self.id())) {
+            run_install_hook("pre_install", hook, ctx)?;
+        }
+        ctx.check_deadline()?;
+        self.create_install_dirs(&ctx.tv, ctx.offline)?;
 
-        if let Err(e) = self.install_version_impl(&ctx) {
+        if let Err(e) = self.install_version_impl(ctx) {
             self.cleanup_install_dirs_on_error(&settings, &ctx.tv);
             return Err(e);
         }
 
         ForgeMeta::write(&ctx.tv.forge)?;
+        if let Some(hook) = settings
+            .post_install
+            .as_ref()
+            .and_then(|h| h.get(self.id()))
+        {
+            if let Err(err) = run_install_hook("post_install", hook, ctx) {
+                warn!("{err:#}");
+            }
+        }
 
         self.cleanup_install_dirs(&settings, &ctx.tv);
         // attempt to touch all the .tool-version files to trigger updates in hook-env
@@ -348,6 +400,7 @@ pub trait Forge: Debug + Send + Sync {
         if let Err(err) = file::remove_file(self.incomplete_file_path(&ctx.tv)) {
             debug!("error removing incomplete file: {:?}", err);
         }
+        INSTALLED_VERSIONS.invalidate();
         ctx.pr.finish_with_message("installed".to_string());
 
         Ok(())
@@ -377,6 +430,7 @@ pub trait Forge: Debug + Send + Sync {
         rmdir(&tv.install_path())?;
         rmdir(&tv.download_path())?;
         rmdir(&tv.cache_path())?;
+        INSTALLED_VERSIONS.invalidate();
         Ok(())
     }
     fn uninstall_version_impl(
@@ -403,14 +457,11 @@ pub trait Forge: Debug + Send + Sync {
     }
 
     fn which(&self, tv: &ToolVersion, bin_name: &str) -> eyre::Result<Option<PathBuf>> {
-        let bin_paths = self.list_bin_paths(tv)?;
-        for bin_path in bin_paths {
-            let bin_path = bin_path.join(bin_name);
-            if bin_path.exists() {
-                return Ok(Some(bin_path));
-            }
-        }
-        Ok(None)
+        let bin_paths = match tv.custom_bin_paths() {
+            Some(bin_paths) => bin_paths,
+            None => self.list_bin_paths(tv)?,
+        };
+        Ok(find_bin(&bin_paths, bin_name))
     }
 
     fn get_lock(&self, path: &Path, force: bool) -> eyre::Result<Option<fslock::LockFile>> {
@@ -426,9 +477,12 @@ pub trait Forge: Debug + Send + Sync {
         };
         Ok(lock)
     }
-    fn create_install_dirs(&self, tv: &ToolVersion) -> eyre::Result<()> {
+    /// offline the downloads are kept since they can't be downloaded again
+    fn create_install_dirs(&self, tv: &ToolVersion, offline: bool) -> eyre::Result<()> {
         let _ = remove_all_with_warning(tv.install_path());
-        let _ = remove_all_with_warning(tv.download_path());
+        if !offline {
+            let _ = remove_all_with_warning(tv.download_path());
+        }
         let _ = remove_all_with_warning(tv.cache_path());
         let _ = file::remove_file(tv.install_path()); // removes if it is a symlink
         file::create_dir_all(tv.install_path())?;
@@ -453,11 +507,28 @@ pub trait Forge: Debug + Send + Sync {
     }
 }
 
+/// runs a pre_install/post_install hook from settings with the version being installed
+fn run_install_hook(name: &str, hook: &str, ctx: &InstallContext) -> eyre::Result<()> {
+    ctx.pr.set_message(format!("{name} {hook}"));
+    CmdLineRunner::new("sh")
+        .arg("-c")
+        .arg(hook)
+        .with_pr(ctx.pr.as_ref())
+        .with_deadline(ctx.deadline)
+        .env("MISE_TOOL_NAME", &ctx.tv.forge.name)
+        .env("MISE_TOOL_VERSION", &ctx.tv.version)
+        .env("MISE_TOOL_INSTALL_PATH", ctx.tv.install_path())
+        .execute()
+        .wrap_err_with(|| format!("{name} hook failed for {}", ctx.tv))
+}
+
 fn fuzzy_match_filter(versions: Vec<String>, query: &str) -> eyre::Result<Vec<String>> {
-    let mut query = query;
-    if query == "latest" {
-        query = "v?[0-9].*";
-    }
+    let query = match query {
+        "latest" => "v?[0-9].*".to_string(),
+        // globs like "lts-*"
+        q if q.contains('*') => q.split('*').map(regex::escape).join(".*"),
+        q => q.to_string(),
+    };
     let query_regex = Regex::new(&format!("^{}([-.].+)?$", query))?;
     let versions = versions
         .into_iter()
@@ -494,14 +565,49 @@ fn rmdir(dir: &Path, pr: &dyn SingleReport) -> eyre::Result<()> {
     })
 }
 
+/// the forge a registry entry stands for, entries for several forges are expanded by
+/// registry::expand instead
 pub fn unalias_forge(forge: &str) -> &str {
-    match forge {
-        "nodejs" => "node",
-        "golang" => "go",
+    match registry::REGISTRY.iter().find(|(name, _)| *name == forge) {
+        Some((_, spec)) if !spec.contains(' ') => spec,
         _ => forge,
     }
 }
 
+/// bins of well-known tools which are named differently from the tool, see forge_for_bin
+static BIN_INDEX: &[(&str, &str)] = &[
+    ("bun", "bunx"),
+    ("erlang", "erl erlc escript"),
+    ("go", "gofmt"),
+    ("java", "jar java javac jshell"),
+    ("node", "corepack npm npx"),
+    ("python", "pip pip3 python3"),
+    ("ruby", "bundle bundler gem irb rake"),
+    ("rust", "cargo rustc rustdoc rustfmt rustup"),
+];
+
+/// the tools which typically provide bin, e.g.: "cargo" is provided by "rust"
+/// this works without the tools being installed so it can suggest what to install
+pub fn forge_for_bin(bin: &str) -> Vec<ForgeArg> {
+    let indexed = BIN_INDEX
+        .iter()
+        .filter(|(_, bins)| bins.split_whitespace().any(|b| b == bin))
+        .map(|(tool, _)| tool.to_string());
+    // most tools are named after their main bin, e.g.: "node" or "deno"
+    let named = CORE_PLUGINS
+        .iter()
+        .map(|p| p.id().to_string())
+        .chain(registry::REGISTRY.iter().map(|(name, _)| name.to_string()))
+        .chain(BIN_INDEX.iter().map(|(tool, _)| tool.to_string()))
+        .filter(|tool| tool == bin)
+        .map(|tool| unalias_forge(&tool).to_string());
+    indexed
+        .chain(named)
+        .unique()
+        .map(|tool| ForgeArg::from(tool.as_str()))
+        .collect()
+}
+
 impl Display for dyn Forge {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.id())
@@ -538,3 +644,28 @@ impl Ord for dyn Forge {
 pub fn reset() {
     *FORGES.lock().unwrap() = None;
 }
+
+/// registers a mock forge so it's returned by get()
+#[cfg(test)]
+pub fn insert(forge: AForge) {
+    load_forges();
+    let mut forges = FORGES.lock().unwrap();
+    forges.as_mut().unwrap().insert(forge.fa().clone(), forge);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forge_for_bin() {
+        let forge_for_bin = |bin| forge_for_bin(bin).iter().map(|fa| fa.id.clone()).join(" ");
+        assert_str_eq!(forge_for_bin("node"), "node");
+        assert_str_eq!(forge_for_bin("npx"), "node");
+        assert_str_eq!(forge_for_bin("cargo"), "rust");
+        assert_str_eq!(forge_for_bin("rust"), "rust");
+        assert_str_eq!(forge_for_bin("gofmt"), "go");
+        assert_str_eq!(forge_for_bin("nodejs"), "node");
+        assert_str_eq!(forge_for_bin("mise-test-unknown-bin"), "");
+    }
+}
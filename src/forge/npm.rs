@@ -59,14 +59,19 @@ impl Forge for NPMForge {
         let config = Config::try_get()?;
         let settings = Settings::get();
         settings.ensure_experimental("npm backend")?;
+        ctx.ensure_online(format!("{}@{} from npm", self.name(), ctx.tv.version))?;
 
-        CmdLineRunner::new("npm")
+        let mut cmd = CmdLineRunner::new("npm")
             .arg("install")
             .arg("-g")
             .arg(&format!("{}@{}", self.name(), ctx.tv.version))
             .arg("--prefix")
-            .arg(ctx.tv.install_path())
-            .with_pr(ctx.pr.as_ref())
+            .arg(ctx.tv.install_path());
+        if let Some(registry) = ctx.tv.source_url() {
+            cmd = cmd.arg("--registry").arg(registry);
+        }
+        cmd.with_pr(ctx.pr.as_ref())
+            .with_deadline(ctx.deadline)
             .envs(ctx.ts.env_with_path(&config)?)
             .prepend_path(ctx.ts.list_paths())?
             .execute()?;
@@ -1,6 +1,9 @@
 use std::fmt::Debug;
 use std::str::FromStr;
 
+use itertools::Itertools;
+use versions::Versioning;
+
 use crate::cache::CacheManager;
 use crate::cli::args::ForgeArg;
 use crate::cmd::CmdLineRunner;
@@ -40,14 +43,7 @@ impl Forge for PIPXForge {
                 PipxRequest::Pypi(package) => {
                     let url = format!("https://pypi.org/pypi/{}/json", package);
                     let raw = crate::http::HTTP_FETCH.get_text(url)?;
-                    let data: serde_json::Value = serde_json::from_str(&raw)?;
-                    let versions = data["releases"]
-                        .as_object()
-                        .ok_or_else(|| eyre::eyre!("Invalid pypi response"))?
-                        .keys()
-                        .map(|k| k.to_string())
-                        .collect();
-                    Ok(versions)
+                    pypi_versions(&raw)
                 }
                 PipxRequest::Git(url) if url.starts_with("https://github.com/") => {
                     let repo = url.strip_prefix("https://github.com/").unwrap();
@@ -69,6 +65,7 @@ impl Forge for PIPXForge {
         let config = Config::try_get()?;
         let settings = Settings::get();
         settings.ensure_experimental("pipx backend")?;
+        ctx.ensure_online(format!("{}@{} from pypi", self.name(), ctx.tv.version))?;
         let pipx_request = self
             .name()
             .parse::<PipxRequest>()?
@@ -78,6 +75,7 @@ impl Forge for PIPXForge {
             .arg("install")
             .arg(pipx_request)
             .with_pr(ctx.pr.as_ref())
+            .with_deadline(ctx.deadline)
             .env("PIPX_HOME", ctx.tv.install_path())
             .env("PIPX_BIN_DIR", ctx.tv.install_path().join("bin"))
             .envs(ctx.ts.env_with_path(&config)?)
@@ -103,6 +101,27 @@ impl PIPXForge {
     }
 }
 
+/// the releases of a package from the PyPI json api, oldest first
+/// releases without files or where every file was yanked can't be installed so they're skipped
+fn pypi_versions(raw: &str) -> eyre::Result<Vec<String>> {
+    let data: serde_json::Value = serde_json::from_str(raw)?;
+    let versions = data["releases"]
+        .as_object()
+        .ok_or_else(|| eyre::eyre!("Invalid pypi response"))?
+        .iter()
+        .filter(|(_, files)| {
+            files.as_array().is_some_and(|files| {
+                files
+                    .iter()
+                    .any(|f| !f["yanked"].as_bool().unwrap_or_default())
+            })
+        })
+        .map(|(v, _)| v.to_string())
+        .sorted_by_cached_key(|v| (Versioning::new(v), v.to_string()))
+        .collect();
+    Ok(versions)
+}
+
 enum PipxRequest {
     /// git+https://github.com/psf/black.git@24.2.0
     /// psf/black@24.2.0
@@ -140,3 +159,25 @@ impl FromStr for PipxRequest {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pypi_versions() {
+        let raw = r#"{
+            "releases": {
+                "1.10.0": [{"yanked": false}],
+                "1.9.0": [{"yanked": false}],
+                "2.0.0": [{"yanked": true}],
+                "2.0.0rc1": [],
+                "1.2.0": [{"yanked": true}, {"yanked": false}]
+            }
+        }"#;
+        assert_eq!(
+            pypi_versions(raw).unwrap(),
+            vec!["1.2.0", "1.9.0", "1.10.0"]
+        );
+    }
+}
@@ -53,6 +53,7 @@ impl Forge for UbiForge {
         let settings = Settings::get();
         let version = &ctx.tv.version;
         settings.ensure_experimental("ubi backend")?;
+        ctx.ensure_online(format!("{}@{version} from GitHub", self.name()))?;
         // Workaround because of not knowing how to pull out the value correctly without quoting
         let path_with_bin = ctx.tv.install_path().join("bin");
 
@@ -62,6 +63,7 @@ impl Forge for UbiForge {
             .arg("--project")
             .arg(self.name())
             .with_pr(ctx.pr.as_ref())
+            .with_deadline(ctx.deadline)
             .envs(ctx.ts.env_with_path(&config)?)
             .prepend_path(ctx.ts.list_paths())?;
 
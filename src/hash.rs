@@ -1,13 +1,15 @@
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::hash::{Hash, Hasher};
 use std::io::{Read, Write};
 use std::path::Path;
+use std::str::FromStr;
 
 use eyre::Result;
 use rayon::prelude::*;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
 
 use crate::file::display_path;
 use crate::ui::progress_report::SingleReport;
@@ -24,11 +26,15 @@ pub fn file_hash_sha256(path: &Path) -> Result<String> {
 }
 
 pub fn file_hash_sha256_prog(path: &Path, pr: Option<&dyn SingleReport>) -> Result<String> {
+    file_hash_prog::<Sha256>(path, pr)
+}
+
+fn file_hash_prog<D: Digest + Write>(path: &Path, pr: Option<&dyn SingleReport>) -> Result<String> {
     let mut file = File::open(path)?;
     if let Some(pr) = pr {
         pr.set_length(file.metadata()?.len());
     }
-    let mut hasher = Sha256::new();
+    let mut hasher = D::new();
     let mut buf = [0; 32 * 1024];
     loop {
         let n = file.read(&mut buf)?;
@@ -59,6 +65,59 @@ pub fn ensure_checksum_sha256(
     Ok(())
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, EnumString, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    Sha512,
+}
+
+/// a checksum pinned in tool options, e.g.: "sha256:abc123..."
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub algorithm: ChecksumAlgorithm,
+    pub digest: String,
+}
+
+impl Checksum {
+    pub fn verify(&self, path: &Path, pr: Option<&dyn SingleReport>) -> Result<()> {
+        let actual = match self.algorithm {
+            ChecksumAlgorithm::Sha256 => file_hash_prog::<Sha256>(path, pr)?,
+            ChecksumAlgorithm::Sha512 => file_hash_prog::<Sha512>(path, pr)?,
+        };
+        ensure!(
+            actual == self.digest,
+            "Checksum mismatch for file {}:\nExpected: {self}\nActual:   {}:{actual}",
+            display_path(path),
+            self.algorithm,
+        );
+        Ok(())
+    }
+}
+
+impl FromStr for Checksum {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((algorithm, digest)) = s.split_once(':') else {
+            bail!("invalid checksum: {s}, expected <algorithm>:<digest>");
+        };
+        let algorithm = algorithm.parse().map_err(|_| {
+            eyre!("unsupported checksum algorithm: {algorithm}, expected sha256 or sha512")
+        })?;
+        Ok(Self {
+            algorithm,
+            digest: digest.to_lowercase(),
+        })
+    }
+}
+
+impl Display for Checksum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.digest)
+    }
+}
+
 pub fn parse_shasums(text: &str) -> HashMap<String, String> {
     text.par_lines()
         .map(|l| {
@@ -72,6 +131,8 @@ pub fn parse_shasums(text: &str) -> HashMap<String, String> {
 
 #[cfg(test)]
 mod tests {
+    use std::{env, fs};
+
     use super::*;
 
     #[test]
@@ -85,4 +146,21 @@ mod tests {
         let hash = file_hash_sha256(path).unwrap();
         assert_snapshot!(hash);
     }
+
+    #[test]
+    fn test_checksum() {
+        let path = env::temp_dir().join("mise-test-checksum");
+        fs::write(&path, "foo\n").unwrap();
+        let sha256 = "sha256:b5bb9d8014a0f9b1d61e21e796d78dccdf1352f23cd32812f4850b878ae4944c";
+        let sha512 = "sha512:0cf9180a764aba863a67b6d72f0918bc131c6772642cb2dce5a34f0a702f9470ddc2bf125c12198b1995c233c34b4afd346c54a2334c350a948a51b6e8b4e6b6";
+        for checksum in [sha256, sha512] {
+            let checksum: Checksum = checksum.parse().unwrap();
+            checksum.verify(&path, None).unwrap();
+        }
+        let checksum: Checksum = "sha256:abc".parse().unwrap();
+        let err = checksum.verify(&path, None).unwrap_err();
+        assert!(err.to_string().contains("Expected: sha256:abc"));
+        assert!("md5:abc".parse::<Checksum>().is_err());
+        assert!("abc".parse::<Checksum>().is_err());
+    }
 }
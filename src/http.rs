@@ -1,7 +1,7 @@
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use eyre::{Report, Result};
 use once_cell::sync::Lazy;
@@ -10,6 +10,7 @@ use reqwest::IntoUrl;
 
 use crate::cli::version;
 use crate::env::MISE_FETCH_REMOTE_VERSIONS_TIMEOUT;
+use crate::errors::Error;
 use crate::file::display_path;
 use crate::ui::progress_report::SingleReport;
 use crate::{env, file};
@@ -26,6 +27,7 @@ pub static HTTP_FETCH: Lazy<Client> =
 #[derive(Debug)]
 pub struct Client {
     reqwest: reqwest::blocking::Client,
+    timeout: Duration,
 }
 
 impl Client {
@@ -35,6 +37,7 @@ impl Client {
                 .timeout(timeout)
                 .connect_timeout(timeout)
                 .build()?,
+            timeout,
         })
     }
 
@@ -45,9 +48,18 @@ impl Client {
     }
 
     pub fn get<U: IntoUrl>(&self, url: U) -> Result<Response> {
+        self.get_until(url, None)
+    }
+
+    /// like get, but the request including reading its body fails once deadline passes
+    fn get_until<U: IntoUrl>(&self, url: U, deadline: Option<Instant>) -> Result<Response> {
         let mut url = url.into_url().unwrap();
         debug!("GET {}", url);
         let mut req = self.reqwest.get(url.clone());
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            req = req.timeout(remaining.min(self.timeout));
+        }
         if url.host_str() == Some("api.github.com") {
             if let Some(token) = &*env::GITHUB_API_TOKEN {
                 req = req.header("authorization", format!("token {}", token));
@@ -59,7 +71,7 @@ impl Client {
             Err(_) if url.scheme() == "http" => {
                 // try with https since http may be blocked
                 url.set_scheme("https").unwrap();
-                return self.get(url);
+                return self.get_until(url, deadline);
             }
             Err(err) => return Err(err.into()),
         };
@@ -68,7 +80,7 @@ impl Client {
         if url.scheme() == "http" && resp.error_for_status_ref().is_err() {
             // try with https since http may be blocked
             url.set_scheme("https").unwrap();
-            return self.get(url);
+            return self.get_until(url, deadline);
         }
         resp.error_for_status_ref()?;
         Ok(resp)
@@ -99,15 +111,32 @@ impl Client {
         Ok(json)
     }
 
+    /// errors with Error::TimedOut if the download hasn't finished by deadline
     pub fn download_file<U: IntoUrl>(
         &self,
         url: U,
         path: &Path,
         pr: Option<&dyn SingleReport>,
+        deadline: Option<Instant>,
     ) -> Result<()> {
         let url = url.into_url()?;
         debug!("GET Downloading {} to {}", &url, display_path(path));
-        let mut resp = self.get(url)?;
+        match self._download_file(url.clone(), path, pr, deadline) {
+            Err(_) if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+                Err(Error::TimedOut(format!("downloading {url}")).into())
+            }
+            res => res,
+        }
+    }
+
+    fn _download_file(
+        &self,
+        url: reqwest::Url,
+        path: &Path,
+        pr: Option<&dyn SingleReport>,
+        deadline: Option<Instant>,
+    ) -> Result<()> {
+        let mut resp = self.get_until(url, deadline)?;
         if let Some(length) = resp.content_length() {
             if let Some(pr) = pr {
                 pr.set_length(length);
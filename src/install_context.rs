@@ -1,3 +1,12 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::errors::Error;
+use crate::http::Client;
 use crate::toolset::{ToolVersion, Toolset};
 use crate::ui::progress_report::SingleReport;
 
@@ -6,4 +15,87 @@ pub struct InstallContext<'a> {
     pub tv: ToolVersion,
     pub pr: Box<dyn SingleReport>,
     pub force: bool,
+    /// only already-downloaded artifacts may be used, see InstallOptions::offline
+    pub offline: bool,
+    pub downloads: &'a DownloadBudget,
+    /// the install is stopped once this passes, see InstallOptions::timeout
+    /// commands run with CmdLineRunner::with_deadline are killed at the deadline
+    pub deadline: Option<Instant>,
+}
+
+impl InstallContext<'_> {
+    /// forges which know the size of what they downloaded report it here before extracting so
+    /// the install can be aborted once Settings::max_download_size is exceeded
+    pub fn add_download(&self, bytes: u64) -> eyre::Result<()> {
+        self.downloads.add(&self.tv, bytes)
+    }
+
+    /// errors offline, called before anything which needs the network and isn't a plain download,
+    /// e.g.: a package manager or a build script which fetches sources
+    pub fn ensure_online(&self, required: impl Display) -> eyre::Result<()> {
+        ensure!(!self.offline, "required {required} but offline");
+        Ok(())
+    }
+
+    /// downloads url to path showing the progress on pr, stopping at the deadline
+    /// offline a file which was already downloaded to path is used instead
+    pub fn download_file(&self, client: &Client, url: &str, path: &Path) -> eyre::Result<()> {
+        if self.offline && path.exists() {
+            self.pr
+                .set_message(format!("using previously downloaded {url}"));
+            return Ok(());
+        }
+        self.ensure_online(url)?;
+        client.download_file(url, path, Some(self.pr.as_ref()), self.deadline)
+    }
+
+    /// errors with Error::TimedOut once the deadline passed, checked between the steps of an
+    /// install so a timed out install doesn't start the next one
+    pub fn check_deadline(&self) -> eyre::Result<()> {
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                Err(Error::TimedOut(self.tv.to_string()).into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// running total of bytes downloaded by every tool in an install
+#[derive(Debug, Default)]
+pub struct DownloadBudget {
+    max: Option<u64>,
+    total: AtomicU64,
+    by_tool: Mutex<HashMap<String, u64>>,
+}
+
+impl DownloadBudget {
+    pub fn new(max: Option<u64>) -> Self {
+        Self {
+            max,
+            total: AtomicU64::new(0),
+            by_tool: Default::default(),
+        }
+    }
+
+    pub fn add(&self, tv: &ToolVersion, bytes: u64) -> eyre::Result<()> {
+        *self
+            .by_tool
+            .lock()
+            .unwrap()
+            .entry(tv.to_string())
+            .or_default() += bytes;
+        let total = self.total.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        match self.max {
+            Some(max) if total > max => bail!(
+                "{tv} brings the total download size to {total} bytes which exceeds max_download_size ({max} bytes)"
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// what tv downloaded, None if its forge didn't report any downloads
+    pub fn downloaded(&self, tv: &ToolVersion) -> Option<u64> {
+        self.by_tool.lock().unwrap().get(&tv.to_string()).copied()
+    }
 }
@@ -2,7 +2,7 @@ use std::env::join_paths;
 use std::ffi::OsString;
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use itertools::Itertools;
 
@@ -26,10 +26,28 @@ impl PathEnv {
         }
     }
 
+    /// a PATH which ignores the inherited one, it only has the added paths, the shims dir, and
+    /// then base
+    pub fn hermetic(base: Vec<PathBuf>) -> Self {
+        Self {
+            post: base,
+            seen_shims: true,
+            ..Self::new()
+        }
+    }
+
     pub fn add(&mut self, path: PathBuf) {
         self.mise.push(path);
     }
 
+    /// removes a path wherever it is, including paths which were already in PATH
+    pub fn remove(&mut self, path: &Path) {
+        self.pre.retain(|p| p != path);
+        self.mise.retain(|p| p != path);
+        self.post.retain(|p| p != path);
+    }
+
+    /// the paths in order, duplicates are dropped after their first occurrence
     pub fn to_vec(&self) -> Vec<PathBuf> {
         let mut paths = self
             .pre
@@ -43,6 +61,7 @@ impl PathEnv {
         paths
             .into_iter()
             .chain(self.post.iter().map(|p| p.to_path_buf()))
+            .unique()
             .collect()
     }
 
@@ -132,4 +151,33 @@ mod tests {
             format!("/1:/2:/3:/before-1:/before-2:/before-3:/after-1:/after-2:/after-3")
         );
     }
+
+    #[test]
+    fn test_path_env_dedupe() {
+        let mut path_env = PathEnv::from_iter(["/before-1", "/1", "/after-1"].map(PathBuf::from));
+        path_env.add("/1".into());
+        path_env.add("/2".into());
+        path_env.add("/1".into());
+        assert_eq!(path_env.to_string(), "/1:/2:/before-1:/after-1");
+    }
+
+    #[test]
+    fn test_path_env_hermetic() {
+        let mut path_env = PathEnv::hermetic(vec!["/usr/bin".into()]);
+        path_env.add("/1".into());
+        assert_eq!(
+            path_env.to_string(),
+            format!("/1:{}:/usr/bin", dirs::SHIMS.to_str().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_path_env_remove() {
+        let mut path_env = PathEnv::from_iter(["/before-1", "/after-1"].map(PathBuf::from));
+        path_env.add("/1".into());
+        path_env.add("/2".into());
+        path_env.remove(Path::new("/2"));
+        path_env.remove(Path::new("/after-1"));
+        assert_eq!(path_env.to_string(), "/1:/before-1");
+    }
 }
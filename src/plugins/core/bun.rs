@@ -14,7 +14,6 @@ use crate::http::{HTTP, HTTP_FETCH};
 use crate::install_context::InstallContext;
 use crate::plugins::core::CorePlugin;
 use crate::toolset::{ToolRequest, ToolVersion};
-use crate::ui::progress_report::SingleReport;
 
 #[derive(Debug)]
 pub struct BunPlugin {
@@ -53,22 +52,23 @@ impl BunPlugin {
         ctx.pr.set_message("bun -v".into());
         CmdLineRunner::new(self.bun_bin(&ctx.tv))
             .with_pr(ctx.pr.as_ref())
+            .with_deadline(ctx.deadline)
             .arg("-v")
             .execute()
     }
 
-    fn download(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> Result<PathBuf> {
+    fn download(&self, ctx: &InstallContext) -> Result<PathBuf> {
         let url = format!(
             "https://github.com/oven-sh/bun/releases/download/bun-v{}/bun-{}-{}.zip",
-            tv.version,
+            ctx.tv.version,
             os(),
             arch()
         );
         let filename = url.split('/').last().unwrap();
-        let tarball_path = tv.download_path().join(filename);
+        let tarball_path = ctx.tv.download_path().join(filename);
 
-        pr.set_message(format!("downloading {filename}"));
-        HTTP.download_file(&url, &tarball_path, Some(pr))?;
+        ctx.pr.set_message(format!("downloading {filename}"));
+        ctx.download_file(&HTTP, &url, &tarball_path)?;
 
         Ok(tarball_path)
     }
@@ -112,9 +112,9 @@ impl Forge for BunPlugin {
         Ok(vec![".bun-version".into()])
     }
 
-    #[requires(matches!(ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. }), "unsupported tool version request type")]
+    #[requires(matches!(ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. } | ToolRequest::Constraint { .. }), "unsupported tool version request type")]
     fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
-        let tarball_path = self.download(&ctx.tv, ctx.pr.as_ref())?;
+        let tarball_path = self.download(ctx)?;
         self.install(ctx, &tarball_path)?;
         self.verify(ctx)?;
 
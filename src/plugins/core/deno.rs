@@ -61,18 +61,18 @@ impl DenoPlugin {
             .execute()
     }
 
-    fn download(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> Result<PathBuf> {
+    fn download(&self, ctx: &InstallContext) -> Result<PathBuf> {
         let url = format!(
             "https://github.com/denoland/deno/releases/download/v{}/deno-{}-{}.zip",
-            tv.version,
+            ctx.tv.version,
             arch(),
             os()
         );
         let filename = url.split('/').last().unwrap();
-        let tarball_path = tv.download_path().join(filename);
+        let tarball_path = ctx.tv.download_path().join(filename);
 
-        pr.set_message(format!("downloading {filename}"));
-        HTTP.download_file(&url, &tarball_path, Some(pr))?;
+        ctx.pr.set_message(format!("downloading {filename}"));
+        ctx.download_file(&HTTP, &url, &tarball_path)?;
 
         // TODO: hash::ensure_checksum_sha256(&tarball_path, &m.sha256)?;
 
@@ -111,9 +111,9 @@ impl Forge for DenoPlugin {
         Ok(vec![".deno-version".into()])
     }
 
-    #[requires(matches!(ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. }), "unsupported tool version request type")]
+    #[requires(matches!(ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. } | ToolRequest::Constraint { .. }), "unsupported tool version request type")]
     fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
-        let tarball_path = self.download(&ctx.tv, ctx.pr.as_ref())?;
+        let tarball_path = self.download(ctx)?;
         self.install(&ctx.tv, ctx.pr.as_ref(), &tarball_path)?;
         self.verify(&ctx.tv, ctx.pr.as_ref())?;
 
@@ -65,6 +65,7 @@ impl ErlangPlugin {
             format!("https://raw.githubusercontent.com/kerl/kerl/{KERL_VERSION}/kerl"),
             &self.kerl_path(),
             None,
+            None,
         )?;
         file::make_executable(&self.kerl_path())?;
         Ok(())
@@ -104,6 +105,8 @@ impl Forge for ErlangPlugin {
     }
 
     fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
+        // kerl downloads the sources itself
+        ctx.ensure_online(format!("the sources of erlang {}", ctx.tv.version))?;
         self.update_kerl()?;
 
         file::remove_all(ctx.tv.install_path())?;
@@ -74,11 +74,8 @@ impl GoPlugin {
         tv.install_path().join("bin")
     }
 
-    fn install_default_packages(
-        &self,
-        tv: &ToolVersion,
-        pr: &dyn SingleReport,
-    ) -> eyre::Result<()> {
+    fn install_default_packages(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        let (tv, pr) = (&ctx.tv, ctx.pr.as_ref());
         let settings = Settings::get();
         let default_packages_file = file::replace_path(&settings.go_default_packages_file);
         let body = file::read_to_string(default_packages_file).unwrap_or_default();
@@ -87,6 +84,7 @@ impl GoPlugin {
             if package.is_empty() {
                 continue;
             }
+            ctx.ensure_online(format!("the default package {package}"))?;
             pr.set_message(format!("installing default package: {}", package));
             let package = if package.contains('@') {
                 package.to_string()
@@ -111,24 +109,30 @@ impl GoPlugin {
             .execute()
     }
 
-    fn download(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> eyre::Result<PathBuf> {
+    fn download(&self, ctx: &InstallContext) -> eyre::Result<PathBuf> {
         let settings = Settings::get();
-        let filename = format!("go{}.{}-{}.tar.gz", tv.version, platform(), arch());
+        let filename = format!("go{}.{}-{}.tar.gz", ctx.tv.version, platform(), arch());
         let tarball_url = format!("{}/{}", &settings.go_download_mirror, &filename);
-        let tarball_path = tv.download_path().join(&filename);
+        let tarball_path = ctx.tv.download_path().join(&filename);
+        // kept next to the tarball so it can be verified offline as well
+        let checksum_url = format!("{}.sha256", &tarball_url);
+        let checksum_path = ctx.tv.download_path().join(format!("{filename}.sha256"));
 
         thread::scope(|s| {
             let checksum_handle = s.spawn(|| {
-                let checksum_url = format!("{}.sha256", &tarball_url);
-                HTTP.get_text(checksum_url)
+                if !(ctx.offline && checksum_path.exists()) {
+                    ctx.ensure_online(&checksum_url)?;
+                    HTTP.download_file(&checksum_url, &checksum_path, None, ctx.deadline)?;
+                }
+                file::read_to_string(&checksum_path)
             });
-            pr.set_message(format!("downloading {filename}"));
-            HTTP.download_file(&tarball_url, &tarball_path, Some(pr))?;
+            ctx.pr.set_message(format!("downloading {filename}"));
+            ctx.download_file(&HTTP, &tarball_url, &tarball_path)?;
 
             if !settings.go_skip_checksum {
-                pr.set_message(format!("verifying {filename}"));
+                ctx.pr.set_message(format!("verifying {filename}"));
                 let checksum = checksum_handle.join().unwrap()?;
-                hash::ensure_checksum_sha256(&tarball_path, &checksum, Some(pr))?;
+                hash::ensure_checksum_sha256(&tarball_path, &checksum, Some(ctx.pr.as_ref()))?;
             }
             Ok(tarball_path)
         })
@@ -152,9 +156,9 @@ impl GoPlugin {
         Ok(())
     }
 
-    fn verify(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> eyre::Result<()> {
-        self.test_go(tv, pr)?;
-        if let Err(err) = self.install_default_packages(tv, pr) {
+    fn verify(&self, ctx: &InstallContext) -> eyre::Result<()> {
+        self.test_go(&ctx.tv, ctx.pr.as_ref())?;
+        if let Err(err) = self.install_default_packages(ctx) {
             warn!("failed to install default go packages: {err:#}");
         }
         let settings = Settings::get();
@@ -200,9 +204,9 @@ impl Forge for GoPlugin {
     }
 
     fn install_version_impl(&self, ctx: &InstallContext) -> eyre::Result<()> {
-        let tarball_path = self.download(&ctx.tv, ctx.pr.as_ref())?;
+        let tarball_path = self.download(ctx)?;
         self.install(&ctx.tv, ctx.pr.as_ref(), &tarball_path)?;
-        self.verify(&ctx.tv, ctx.pr.as_ref())?;
+        self.verify(ctx)?;
 
         Ok(())
     }
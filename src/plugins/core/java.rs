@@ -51,14 +51,19 @@ impl JavaPlugin {
         }
     }
 
-    fn fetch_java_metadata(&self, release_type: &str) -> Result<&HashMap<String, JavaMetadata>> {
+    /// offline only the cached metadata is used, see CacheManager::get_or_try_init_offline
+    fn fetch_java_metadata(
+        &self,
+        release_type: &str,
+        offline: bool,
+    ) -> Result<&HashMap<String, JavaMetadata>> {
         let cache = if release_type == "ea" {
             &self.java_metadata_ea_cache
         } else {
             &self.java_metadata_ga_cache
         };
         let release_type = release_type.to_string();
-        cache.get_or_try_init(|| {
+        cache.get_or_try_init_offline(offline, || {
             let mut metadata = HashMap::new();
 
             for m in self.download_java_metadata(&release_type)?.into_iter() {
@@ -87,7 +92,7 @@ impl JavaPlugin {
         //     Err(e) => warn!("failed to fetch remote versions: {}", e),
         // }
         let versions = self
-            .fetch_java_metadata("ga")?
+            .fetch_java_metadata("ga", false)?
             .iter()
             .sorted_by_cached_key(|(v, m)| {
                 let is_shorthand = regex!(r"^\d").is_match(v);
@@ -126,19 +131,14 @@ impl JavaPlugin {
             .execute()
     }
 
-    fn download(
-        &self,
-        tv: &ToolVersion,
-        pr: &dyn SingleReport,
-        m: &JavaMetadata,
-    ) -> Result<PathBuf> {
+    fn download(&self, ctx: &InstallContext, m: &JavaMetadata) -> Result<PathBuf> {
         let filename = m.url.split('/').last().unwrap();
-        let tarball_path = tv.download_path().join(filename);
+        let tarball_path = ctx.tv.download_path().join(filename);
 
-        pr.set_message(format!("downloading {filename}"));
-        HTTP.download_file(&m.url, &tarball_path, Some(pr))?;
+        ctx.pr.set_message(format!("downloading {filename}"));
+        ctx.download_file(&HTTP, &m.url, &tarball_path)?;
 
-        hash::ensure_checksum_sha256(&tarball_path, &m.sha256, Some(pr))?;
+        hash::ensure_checksum_sha256(&tarball_path, &m.sha256, Some(ctx.pr.as_ref()))?;
 
         Ok(tarball_path)
     }
@@ -268,11 +268,11 @@ impl JavaPlugin {
         }
     }
 
-    fn tv_to_metadata(&self, tv: &ToolVersion) -> Result<&JavaMetadata> {
+    fn tv_to_metadata(&self, tv: &ToolVersion, offline: bool) -> Result<&JavaMetadata> {
         let v: String = self.tv_to_java_version(tv);
         let release_type = self.tv_release_type(tv);
         let m = self
-            .fetch_java_metadata(&release_type)?
+            .fetch_java_metadata(&release_type, offline)?
             .get(&v)
             .ok_or_else(|| eyre!("no metadata found for version {}", tv.version))?;
         Ok(m)
@@ -367,10 +367,10 @@ impl Forge for JavaPlugin {
         }
     }
 
-    #[requires(matches!(ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. }), "unsupported tool version request type")]
+    #[requires(matches!(ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. } | ToolRequest::Constraint { .. }), "unsupported tool version request type")]
     fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
-        let metadata = self.tv_to_metadata(&ctx.tv)?;
-        let tarball_path = self.download(&ctx.tv, ctx.pr.as_ref(), metadata)?;
+        let metadata = self.tv_to_metadata(&ctx.tv, ctx.offline)?;
+        let tarball_path = self.download(ctx, metadata)?;
         self.install(&ctx.tv, ctx.pr.as_ref(), &tarball_path, metadata)?;
         self.verify(&ctx.tv, ctx.pr.as_ref())?;
 
@@ -62,7 +62,7 @@ impl NodePlugin {
 
     fn install_precompiled(&self, ctx: &InstallContext, opts: &BuildOpts) -> Result<()> {
         match self.fetch_tarball(
-            ctx.pr.as_ref(),
+            ctx,
             &opts.binary_tarball_url,
             &opts.binary_tarball_path,
             &opts.version,
@@ -86,7 +86,7 @@ impl NodePlugin {
     fn install_compiled(&self, ctx: &InstallContext, opts: &BuildOpts) -> Result<()> {
         let tarball_name = &opts.source_tarball_name;
         self.fetch_tarball(
-            ctx.pr.as_ref(),
+            ctx,
             &opts.source_tarball_url,
             &opts.source_tarball_path,
             &opts.version,
@@ -102,21 +102,22 @@ impl NodePlugin {
 
     fn fetch_tarball(
         &self,
-        pr: &dyn SingleReport,
+        ctx: &InstallContext,
         url: &Url,
         local: &Path,
         version: &str,
     ) -> Result<()> {
         let tarball_name = local.file_name().unwrap().to_string_lossy().to_string();
         if local.exists() {
-            pr.set_message(format!("using previously downloaded {tarball_name}"));
+            ctx.pr
+                .set_message(format!("using previously downloaded {tarball_name}"));
         } else {
-            pr.set_message(format!("downloading {tarball_name}"));
-            HTTP.download_file(url.clone(), local, Some(pr))?;
+            ctx.pr.set_message(format!("downloading {tarball_name}"));
+            ctx.download_file(&HTTP, url.as_str(), local)?;
         }
         if *env::MISE_NODE_VERIFY {
-            pr.set_message(format!("verifying {tarball_name}"));
-            self.verify(local, version, pr)?;
+            ctx.pr.set_message(format!("verifying {tarball_name}"));
+            self.verify(ctx, local, version)?;
         }
         Ok(())
     }
@@ -125,6 +126,7 @@ impl NodePlugin {
         let mut cmd = CmdLineRunner::new("sh")
             .prepend_path(opts.path.clone())?
             .with_pr(ctx.pr.as_ref())
+            .with_deadline(ctx.deadline)
             .current_dir(&opts.build_dir)
             .arg("-c");
         if let Some(cflags) = &*env::MISE_NODE_CFLAGS {
@@ -143,13 +145,17 @@ impl NodePlugin {
         self.sh(ctx, opts)?.arg(&opts.make_install_cmd).execute()
     }
 
-    fn verify(&self, tarball: &Path, version: &str, pr: &dyn SingleReport) -> Result<()> {
+    fn verify(&self, ctx: &InstallContext, tarball: &Path, version: &str) -> Result<()> {
         let tarball_name = tarball.file_name().unwrap().to_string_lossy().to_string();
         // TODO: verify gpg signature
-        let shasums = HTTP.get_text(self.shasums_url(version)?)?;
-        let shasums = hash::parse_shasums(&shasums);
+        // kept next to the tarball so it can be verified offline as well
+        let shasums_path = tarball.with_file_name("SHASUMS256.txt");
+        if !shasums_path.exists() {
+            ctx.download_file(&HTTP, self.shasums_url(version)?.as_str(), &shasums_path)?;
+        }
+        let shasums = hash::parse_shasums(&file::read_to_string(&shasums_path)?);
         let shasum = shasums.get(&tarball_name).unwrap();
-        hash::ensure_checksum_sha256(tarball, shasum, Some(pr))
+        hash::ensure_checksum_sha256(tarball, shasum, Some(ctx.pr.as_ref()))
     }
 
     fn node_path(&self, tv: &ToolVersion) -> PathBuf {
@@ -164,18 +170,15 @@ impl NodePlugin {
         tv.install_path().join("bin/corepack")
     }
 
-    fn install_default_packages(
-        &self,
-        config: &Config,
-        tv: &ToolVersion,
-        pr: &dyn SingleReport,
-    ) -> Result<()> {
+    fn install_default_packages(&self, config: &Config, ctx: &InstallContext) -> Result<()> {
+        let (tv, pr) = (&ctx.tv, ctx.pr.as_ref());
         let body = file::read_to_string(&*env::MISE_NODE_DEFAULT_PACKAGES_FILE).unwrap_or_default();
         for package in body.lines() {
             let package = package.split('#').next().unwrap_or_default().trim();
             if package.is_empty() {
                 continue;
             }
+            ctx.ensure_online(format!("the default package {package}"))?;
             pr.set_message(format!("installing default package: {}", package));
             let npm = self.npm_path(tv);
             CmdLineRunner::new(npm)
@@ -302,7 +305,7 @@ impl Forge for NodePlugin {
         self.test_node(&config, &ctx.tv, ctx.pr.as_ref())?;
         self.install_npm_shim(&ctx.tv)?;
         self.test_npm(&config, &ctx.tv, ctx.pr.as_ref())?;
-        if let Err(err) = self.install_default_packages(&config, &ctx.tv, ctx.pr.as_ref()) {
+        if let Err(err) = self.install_default_packages(&config, ctx) {
             warn!("failed to install default npm packages: {err:#}");
         }
         if *env::MISE_NODE_COREPACK && self.corepack_path(&ctx.tv).exists() {
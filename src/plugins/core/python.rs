@@ -94,8 +94,12 @@ impl PythonPlugin {
         tv.install_short_path().join("bin/python")
     }
 
-    fn fetch_precompiled_remote_versions(&self) -> eyre::Result<&Vec<(String, String, String)>> {
-        self.precompiled_cache.get_or_try_init(|| {
+    /// offline only the cached versions are used, see CacheManager::get_or_try_init_offline
+    fn fetch_precompiled_remote_versions(
+        &self,
+        offline: bool,
+    ) -> eyre::Result<&Vec<(String, String, String)>> {
+        self.precompiled_cache.get_or_try_init_offline(offline, || {
             let settings = Settings::get();
             let raw = HTTP_FETCH.get_text("http://mise-versions.jdx.dev/python-precompiled")?;
             let platform = format!("{}-{}", python_arch(&settings), python_os(&settings));
@@ -119,7 +123,7 @@ impl PythonPlugin {
     }
 
     fn install_precompiled(&self, ctx: &InstallContext) -> eyre::Result<()> {
-        let precompiled_versions = self.fetch_precompiled_remote_versions()?;
+        let precompiled_versions = self.fetch_precompiled_remote_versions(ctx.offline)?;
         let precompile_info = precompiled_versions
             .iter()
             .rev()
@@ -154,7 +158,7 @@ impl PythonPlugin {
         let tarball_path = download.join(filename);
 
         ctx.pr.set_message(format!("downloading {filename}"));
-        HTTP.download_file(&url, &tarball_path, Some(ctx.pr.as_ref()))?;
+        ctx.download_file(&HTTP, &url, &tarball_path)?;
 
         ctx.pr.set_message(format!("installing {filename}"));
         file::untar(&tarball_path, &download)?;
@@ -167,6 +171,8 @@ impl PythonPlugin {
     fn install_compiled(&self, ctx: &InstallContext) -> eyre::Result<()> {
         let config = Config::get();
         let settings = Settings::get();
+        // python-build downloads the sources itself
+        ctx.ensure_online(format!("the sources of python {}", ctx.tv.version))?;
         self.install_or_update_python_build()?;
         if matches!(&ctx.tv.request, ToolRequest::Ref { .. }) {
             return Err(eyre!("Ref versions not supported for python"));
@@ -174,6 +180,7 @@ impl PythonPlugin {
         ctx.pr.set_message("Running python-build".into());
         let mut cmd = CmdLineRunner::new(self.python_build_bin())
             .with_pr(ctx.pr.as_ref())
+            .with_deadline(ctx.deadline)
             .arg(ctx.tv.version.as_str())
             .arg(&ctx.tv.install_path())
             .env("PIP_REQUIRE_VIRTUALENV", "false")
@@ -206,12 +213,13 @@ impl PythonPlugin {
         &self,
         config: &Config,
         packages_file: &Path,
-        tv: &ToolVersion,
-        pr: &dyn SingleReport,
+        ctx: &InstallContext,
     ) -> eyre::Result<()> {
+        let (tv, pr) = (&ctx.tv, ctx.pr.as_ref());
         if !packages_file.exists() {
             return Ok(());
         }
+        ctx.ensure_online("the default packages")?;
         pr.set_message("installing default packages".into());
         CmdLineRunner::new(tv.install_path().join("bin/python"))
             .with_pr(pr)
@@ -314,7 +322,7 @@ impl Forge for PythonPlugin {
     fn _list_remote_versions(&self) -> eyre::Result<Vec<String>> {
         if Settings::get().python_compile == Some(false) {
             Ok(self
-                .fetch_precompiled_remote_versions()?
+                .fetch_precompiled_remote_versions(false)?
                 .iter()
                 .map(|(v, _, _)| v.clone())
                 .collect())
@@ -343,9 +351,7 @@ impl Forge for PythonPlugin {
             warn!("failed to get virtualenv: {e:#}");
         }
         if let Some(default_file) = &settings.python_default_packages_file {
-            if let Err(err) =
-                self.install_default_packages(&config, default_file, &ctx.tv, ctx.pr.as_ref())
-            {
+            if let Err(err) = self.install_default_packages(&config, default_file, ctx) {
                 warn!("failed to install default python packages: {err:#}");
             }
         }
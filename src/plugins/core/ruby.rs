@@ -356,14 +356,17 @@ impl Forge for RubyPlugin {
         Ok(v)
     }
 
-    #[requires(matches!(ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. }), "unsupported tool version request type")]
+    #[requires(matches!(ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. } | ToolRequest::Constraint { .. }), "unsupported tool version request type")]
     fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
+        // ruby-build and ruby-install download the sources themselves
+        ctx.ensure_online(format!("the sources of ruby {}", ctx.tv.version))?;
         if let Err(err) = self.update_build_tool() {
             warn!("ruby build tool update error: {err:#}");
         }
         ctx.pr.set_message("running ruby-build".into());
         let config = Config::get();
         self.install_cmd(&config, &ctx.tv, ctx.pr.as_ref())?
+            .with_deadline(ctx.deadline)
             .execute()?;
 
         self.test_ruby(&config, &ctx.tv, ctx.pr.as_ref())?;
@@ -14,7 +14,6 @@ use crate::http::{HTTP, HTTP_FETCH};
 use crate::install_context::InstallContext;
 use crate::plugins::core::CorePlugin;
 use crate::toolset::{ToolRequest, ToolVersion};
-use crate::ui::progress_report::SingleReport;
 
 #[derive(Debug)]
 pub struct ZigPlugin {
@@ -35,6 +34,7 @@ impl ZigPlugin {
         ctx.pr.set_message("zig version".into());
         CmdLineRunner::new(self.zig_bin(&ctx.tv))
             .with_pr(ctx.pr.as_ref())
+            .with_deadline(ctx.deadline)
             .arg("version")
             .execute()
     }
@@ -57,29 +57,29 @@ impl ZigPlugin {
         Ok(versions)
     }
 
-    fn download(&self, tv: &ToolVersion, pr: &dyn SingleReport) -> Result<PathBuf> {
-        let url = if tv.version == "ref:master" {
+    fn download(&self, ctx: &InstallContext) -> Result<PathBuf> {
+        let url = if ctx.tv.version == "ref:master" {
             format!(
                 "https://ziglang.org/builds/zig-{}-{}-{}.tar.xz",
                 os(),
                 arch(),
-                self.get_master_version()?
+                self.get_master_version(ctx)?
             )
         } else {
             format!(
                 "https://ziglang.org/download/{}/zig-{}-{}-{}.tar.xz",
-                tv.version,
+                ctx.tv.version,
                 os(),
                 arch(),
-                tv.version
+                ctx.tv.version
             )
         };
 
         let filename = url.split('/').last().unwrap();
-        let tarball_path = tv.download_path().join(filename);
+        let tarball_path = ctx.tv.download_path().join(filename);
 
-        pr.set_message(format!("downloading {filename}"));
-        HTTP.download_file(&url, &tarball_path, Some(pr))?;
+        ctx.pr.set_message(format!("downloading {filename}"));
+        ctx.download_file(&HTTP, &url, &tarball_path)?;
 
         Ok(tarball_path)
     }
@@ -95,7 +95,7 @@ impl ZigPlugin {
                 os(),
                 arch(),
                 if ctx.tv.version == "ref:master" {
-                    self.get_master_version()?
+                    self.get_master_version(ctx)?
                 } else {
                     ctx.tv.version.clone()
                 }
@@ -115,9 +115,10 @@ impl ZigPlugin {
         self.test_zig(ctx)
     }
 
-    fn get_master_version(&self) -> Result<String> {
-        let version_json: serde_json::Value =
-            HTTP_FETCH.json("https://ziglang.org/download/index.json")?;
+    fn get_master_version(&self, ctx: &InstallContext) -> Result<String> {
+        let url = "https://ziglang.org/download/index.json";
+        ctx.ensure_online(url)?;
+        let version_json: serde_json::Value = HTTP_FETCH.json(url)?;
         let master_version = version_json
             .pointer("/master/version")
             .and_then(|v| v.as_str())
@@ -141,9 +142,9 @@ impl Forge for ZigPlugin {
     fn legacy_filenames(&self) -> Result<Vec<String>> {
         Ok(vec![".zig-version".into()])
     }
-    #[requires(matches ! (ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. } | ToolRequest::Ref { .. }), "unsupported tool version request type")]
+    #[requires(matches ! (ctx.tv.request, ToolRequest::Version { .. } | ToolRequest::Prefix { .. } | ToolRequest::Constraint { .. } | ToolRequest::Ref { .. }), "unsupported tool version request type")]
     fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
-        let tarball_path = self.download(&ctx.tv, ctx.pr.as_ref())?;
+        let tarball_path = self.download(ctx)?;
         self.install(ctx, &tarball_path)?;
         self.verify(ctx)?;
         Ok(())
@@ -312,10 +312,9 @@ impl ExternalPlugin {
         Ok(bin_paths)
     }
     fn fetch_exec_env(&self, ts: &Toolset, tv: &ToolVersion) -> Result<BTreeMap<String, String>> {
-        let mut sm = self.script_man_for_tv(tv)?;
-        for p in ts.list_paths() {
-            sm.prepend_path(p);
-        }
+        let sm = self
+            .script_man_for_tv(tv)?
+            .with_env("PATH", ts.exec_env_path()?);
         let script = sm.get_script_path(&ExecEnv);
         let ed = EnvDiff::from_bash_script(&script, &sm.env)?;
         let env = ed
@@ -345,7 +344,9 @@ impl ExternalPlugin {
             sm = sm.with_env("MISE_PROJECT_ROOT", project_root);
         }
         let install_type = match &tv.request {
-            ToolRequest::Version { .. } | ToolRequest::Prefix { .. } => "version",
+            ToolRequest::Version { .. }
+            | ToolRequest::Prefix { .. }
+            | ToolRequest::Constraint { .. } => "version",
             ToolRequest::Ref { .. } => "ref",
             ToolRequest::Path(_, _) => "path",
             ToolRequest::Sub { .. } => "sub",
@@ -393,7 +394,7 @@ impl ExternalPlugin {
         sm.env.extend(env);
         if sm.script_exists(&script) {
             pr.set_message(format!("executing {hook} hook"));
-            sm.run_by_line(&script, pr)?;
+            sm.run_by_line(&script, pr, None)?;
         }
         Ok(())
     }
@@ -714,13 +715,15 @@ impl Forge for ExternalPlugin {
     }
 
     fn install_version_impl(&self, ctx: &InstallContext) -> Result<()> {
+        // the scripts may download anything so they can't be restricted to what's been downloaded
+        ctx.ensure_online(format!("the install scripts of the {} plugin", self.name))?;
         let mut sm = self.script_man_for_tv(&ctx.tv)?;
 
         for p in ctx.ts.list_paths() {
             sm.prepend_path(p);
         }
 
-        let run_script = |script| sm.run_by_line(script, ctx.pr.as_ref());
+        let run_script = |script| sm.run_by_line(script, ctx.pr.as_ref(), ctx.deadline);
 
         if sm.script_exists(&Download) {
             ctx.pr.set_message("downloading".into());
@@ -736,7 +739,7 @@ impl Forge for ExternalPlugin {
     fn uninstall_version_impl(&self, pr: &dyn SingleReport, tv: &ToolVersion) -> Result<()> {
         if self.plugin_path.join("bin/uninstall").exists() {
             self.script_man_for_tv(tv)?
-                .run_by_line(&Script::Uninstall, pr)?;
+                .run_by_line(&Script::Uninstall, pr, None)?;
         }
         Ok(())
     }
@@ -3,6 +3,7 @@ use std::ffi::OsString;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
+use std::time::Instant;
 
 use color_eyre::eyre::{Context, Result};
 use duct::Expression;
@@ -163,16 +164,26 @@ impl ScriptManager {
             .wrap_err_with(|| ScriptFailed(display_path(self.get_script_path(script)), None))
     }
 
-    pub fn run_by_line(&self, script: &Script, pr: &dyn SingleReport) -> Result<()> {
+    /// the script is killed once the deadline passes, see CmdLineRunner::with_deadline
+    pub fn run_by_line(
+        &self,
+        script: &Script,
+        pr: &dyn SingleReport,
+        deadline: Option<Instant>,
+    ) -> Result<()> {
         let path = self.get_script_path(script);
         pr.set_message(display_path(&path));
         let cmd = CmdLineRunner::new(path.clone())
             .with_pr(pr)
+            .with_deadline(deadline)
             .env_clear()
             .envs(&self.env);
         if let Err(e) = cmd.execute() {
             let status = match e.downcast_ref::<Error>() {
                 Some(ScriptFailed(_, status)) => *status,
+                Some(Error::TimedOut(_)) => {
+                    return Err(Error::TimedOut(display_path(&path)).into());
+                }
                 _ => None,
             };
             return Err(ScriptFailed(display_path(&path), status).into());
@@ -0,0 +1,103 @@
+use once_cell::sync::Lazy;
+use strum::IntoEnumIterator;
+
+use crate::forge::ForgeType;
+
+/// short names of tools and the forge specs they stand for, e.g.: "nodejs" is installed as "node"
+/// a value can list several forge specs separated by spaces to install a bundle of tools
+static ENTRIES: &[(&str, &str)] = &[("golang", "go"), ("nodejs", "node"), ("ubi", "cargo:ubi")];
+
+/// ENTRIES and the builtin aliases of every ForgeType, an entry in ENTRIES wins over a builtin
+/// alias with the same name
+pub static REGISTRY: Lazy<Vec<(&str, &str)>> = Lazy::new(|| {
+    let builtins = ForgeType::iter().flat_map(|ft| ft.builtin_aliases().iter().copied());
+    with_builtin_aliases(ENTRIES, builtins)
+});
+
+fn with_builtin_aliases(
+    entries: &[(&'static str, &'static str)],
+    builtins: impl IntoIterator<Item = (&'static str, &'static str)>,
+) -> Vec<(&'static str, &'static str)> {
+    let mut registry = entries.to_vec();
+    for (name, spec) in builtins {
+        if !registry.iter().any(|(n, _)| *n == name) {
+            registry.push((name, spec));
+        }
+    }
+    registry
+}
+
+/// the forge specs a name stands for, names which aren't in the registry stand for themselves
+pub fn expand(s: &str) -> Vec<String> {
+    expand_with(&REGISTRY, s)
+}
+
+fn expand_with(registry: &[(&str, &str)], s: &str) -> Vec<String> {
+    _expand(registry, s, &mut vec![])
+}
+
+/// entries may refer to other entries, an entry which refers back to itself is not expanded again
+fn _expand(registry: &[(&str, &str)], s: &str, expanding: &mut Vec<String>) -> Vec<String> {
+    let Some((_, specs)) = registry.iter().find(|(name, _)| *name == s) else {
+        return vec![s.to_string()];
+    };
+    if expanding.iter().any(|e| e == s) {
+        warn!("registry entry {s} refers to itself");
+        return vec![s.to_string()];
+    }
+    expanding.push(s.to_string());
+    let expanded = specs
+        .split_whitespace()
+        .flat_map(|spec| _expand(registry, spec, expanding))
+        .collect();
+    expanding.pop();
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(expand("nodejs"), vec!["node"]);
+        assert_eq!(expand("cargo:eza"), vec!["cargo:eza"]);
+
+        let registry = [
+            ("python-tools", "pipx:black pipx:ruff"),
+            ("dev-tools", "python-tools cargo:eza"),
+        ];
+        assert_eq!(
+            expand_with(&registry, "python-tools"),
+            vec!["pipx:black", "pipx:ruff"]
+        );
+        assert_eq!(
+            expand_with(&registry, "dev-tools"),
+            vec!["pipx:black", "pipx:ruff", "cargo:eza"]
+        );
+    }
+
+    #[test]
+    fn test_builtin_aliases() {
+        assert_eq!(expand("ripgrep"), vec!["cargo:ripgrep"]);
+        assert_eq!(expand("prettier"), vec!["npm:prettier"]);
+
+        let registry = with_builtin_aliases(
+            &[("ripgrep", "ubi:BurntSushi/ripgrep")],
+            [("ripgrep", "cargo:ripgrep"), ("black", "pipx:black")],
+        );
+        assert_eq!(
+            registry,
+            vec![
+                ("ripgrep", "ubi:BurntSushi/ripgrep"),
+                ("black", "pipx:black")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_recursion() {
+        let registry = [("a", "b tiny"), ("b", "a dummy")];
+        assert_eq!(expand_with(&registry, "a"), vec!["a", "dummy", "tiny"]);
+    }
+}
@@ -10,6 +10,7 @@ use eyre::WrapErr;
 use itertools::Itertools;
 use rayon::prelude::*;
 
+use crate::cli::args::ForgeArg;
 use crate::cli::exec::Exec;
 use crate::config::{Config, Settings};
 use crate::file::{create_dir_all, display_path, remove_all};
@@ -37,6 +38,7 @@ pub fn handle_shim() -> Result<()> {
         command: Some(args),
         jobs: None,
         raw: false,
+        without: vec![],
     };
     exec.run()?;
     exit(0);
@@ -95,39 +97,131 @@ pub fn reshim(ts: &Toolset) -> Result<()> {
 
     create_dir_all(*dirs::SHIMS)?;
 
-    let (shims_to_add, shims_to_remove) = get_shim_diffs(&mise_bin, ts)?;
+    let changes = ShimChanges::new(&mise_bin, ts)?;
+    if changes.is_empty() {
+        // e.g.: a new version of a tool which has the same bins as before
+        trace!("shims are up to date");
+        return Ok(());
+    }
 
-    for shim in shims_to_add {
+    add_shims(&mise_bin, changes.add)?;
+    for shim in changes.remove {
         let symlink_path = dirs::SHIMS.join(shim);
-        file::make_symlink(&mise_bin, &symlink_path).wrap_err_with(|| {
+        remove_all(&symlink_path)?;
+    }
+    for (name, target) in changes.plugin_shims {
+        make_shim(&target, &dirs::SHIMS.join(name))?;
+    }
+    Ok(())
+}
+
+/// whether `reshim` would change anything, only compares shim names so it is cheap enough to
+/// call before every reshim
+pub fn needs_reshim(ts: &Toolset) -> Result<bool> {
+    if !dirs::SHIMS.exists() {
+        return Ok(true);
+    }
+    let mise_bin = file::which("mise").unwrap_or(env::MISE_BIN.clone());
+    Ok(!ShimChanges::new(&mise_bin, ts)?.is_empty())
+}
+
+/// what reshim needs to do to make the shims dir match the toolset
+struct ShimChanges {
+    add: BTreeSet<String>,
+    remove: Vec<String>,
+    plugin_shims: Vec<(String, PathBuf)>,
+    plugin_shims_missing: bool,
+}
+
+impl ShimChanges {
+    fn new(mise_bin: &Path, ts: &Toolset) -> Result<Self> {
+        let (add, remove) = get_shim_diffs(mise_bin, ts)?;
+        let plugin_shims = list_plugin_shims(&forge::list())?;
+        // plugin shims are rewritten rather than removed
+        let remove = remove
+            .into_iter()
+            .filter(|shim| !plugin_shims.iter().any(|(name, _)| name == shim))
+            .collect_vec();
+        let plugin_shims_missing = plugin_shims
+            .iter()
+            .any(|(name, _)| !dirs::SHIMS.join(name).exists());
+        Ok(Self {
+            add,
+            remove,
+            plugin_shims,
+            plugin_shims_missing,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.remove.is_empty() && !self.plugin_shims_missing
+    }
+}
+
+/// only adds the shims of the given tools, e.g.: after installing them
+/// shims are never removed since other tools may provide the same bins, use `reshim` for that
+pub fn reshim_for(ts: &Toolset, forges: &[ForgeArg]) -> Result<()> {
+    let _lock = LockFile::new(&dirs::SHIMS)
+        .with_callback(|l| {
+            trace!("reshim callback {}", l.display());
+        })
+        .lock();
+
+    let mise_bin = file::which("mise").unwrap_or(env::MISE_BIN.clone());
+
+    create_dir_all(*dirs::SHIMS)?;
+
+    let actual_shims = get_actual_shims(&mise_bin)?;
+    let desired_shims = ts
+        .list_installed_versions()?
+        .into_par_iter()
+        .filter(|(t, _)| forges.contains(t.fa()))
+        .flat_map(|(t, tv)| {
+            list_tool_bins(t.clone(), &tv).unwrap_or_else(|e| {
+                warn!("Error listing bin paths for {}: {:#}", tv, e);
+                Vec::new()
+            })
+        })
+        .collect::<HashSet<_>>();
+    add_shims(&mise_bin, desired_shims.difference(&actual_shims))?;
+    make_plugin_shims(forges.iter().map(forge::get).collect())
+}
+
+fn add_shims(mise_bin: &Path, shims: impl IntoIterator<Item = impl AsRef<str>>) -> Result<()> {
+    for shim in shims {
+        let symlink_path = dirs::SHIMS.join(shim.as_ref());
+        file::make_symlink(mise_bin, &symlink_path).wrap_err_with(|| {
             eyre!(
                 "Failed to create symlink from {} to {}",
-                display_path(&mise_bin),
+                display_path(mise_bin),
                 display_path(&symlink_path)
             )
         })?;
     }
-    for shim in shims_to_remove {
-        let symlink_path = dirs::SHIMS.join(shim);
-        remove_all(&symlink_path)?;
+    Ok(())
+}
+
+// plugins can provide their own shims in a "shims" directory
+fn make_plugin_shims(plugins: Vec<Arc<dyn Forge>>) -> Result<()> {
+    for (name, target) in list_plugin_shims(&plugins)? {
+        make_shim(&target, &dirs::SHIMS.join(name))?;
     }
-    for plugin in forge::list() {
-        match dirs::PLUGINS.join(plugin.id()).join("shims").read_dir() {
-            Ok(files) => {
-                for bin in files {
-                    let bin = bin?;
-                    let bin_name = bin.file_name().into_string().unwrap();
-                    let symlink_path = dirs::SHIMS.join(bin_name);
-                    make_shim(&bin.path(), &symlink_path)?;
-                }
-            }
-            Err(_) => {
-                continue;
-            }
+    Ok(())
+}
+
+/// the (shim name, target) of the shims the plugins provide
+fn list_plugin_shims(plugins: &[Arc<dyn Forge>]) -> Result<Vec<(String, PathBuf)>> {
+    let mut shims = vec![];
+    for plugin in plugins {
+        let Ok(files) = dirs::PLUGINS.join(plugin.id()).join("shims").read_dir() else {
+            continue;
+        };
+        for bin in files {
+            let bin = bin?;
+            shims.push((bin.file_name().into_string().unwrap(), bin.path()));
         }
     }
-
-    Ok(())
+    Ok(shims)
 }
 
 // get_shim_diffs contrasts the actual shims on disk
@@ -163,11 +257,22 @@ fn get_actual_shims(mise_bin: impl AsRef<Path>) -> Result<HashSet<String>> {
         .filter(|bin| {
             let path = dirs::SHIMS.join(bin);
 
-            !path.is_symlink() || path.read_link().is_ok_and(|p| p == mise_bin)
+            match path.is_symlink() {
+                true => path.read_link().is_ok_and(|p| p == mise_bin),
+                // files mise didn't create are left alone so they're never removed
+                false => is_plugin_shim(&path),
+            }
         })
         .collect::<HashSet<_>>())
 }
 
+// marks the shims created by make_shim
+const SHIM_MARKER: &str = "# shim created by mise";
+
+fn is_plugin_shim(path: &Path) -> bool {
+    file::read_to_string(path).is_ok_and(|s| s.lines().nth(1) == Some(SHIM_MARKER))
+}
+
 fn list_executables_in_dir(dir: &Path) -> Result<HashSet<String>> {
     Ok(dir
         .read_dir()?
@@ -204,7 +309,8 @@ fn get_desired_shims(toolset: &Toolset) -> Result<HashSet<String>> {
 
 // lists all the paths to bins in a tv that shims will be needed for
 fn list_tool_bins(t: Arc<dyn Forge>, tv: &ToolVersion) -> Result<Vec<String>> {
-    Ok(t.list_bin_paths(tv)?
+    Ok(tv
+        .list_bin_paths(t.as_ref())?
         .into_iter()
         .par_bridge()
         .filter(|path| path.exists())
@@ -223,6 +329,7 @@ fn make_shim(target: &Path, shim: &Path) -> Result<()> {
         shim,
         formatdoc! {r#"
         #!/bin/sh
+        {SHIM_MARKER}
         export ASDF_DATA_DIR={data_dir}
         export PATH="{fake_asdf_dir}:$PATH"
         mise x -- {target} "$@"
@@ -242,7 +349,15 @@ fn make_shim(target: &Path, shim: &Path) -> Result<()> {
 
 fn err_no_version_set(ts: Toolset, bin_name: &str, tvs: Vec<ToolVersion>) -> Result<PathBuf> {
     if tvs.is_empty() {
-        bail!("{} is not a valid shim", bin_name);
+        let forges = forge::forge_for_bin(bin_name);
+        if forges.is_empty() {
+            bail!("{} is not a valid shim", bin_name);
+        }
+        let mut msg = format!("{bin_name} is not a valid shim, it is usually provided by:\n");
+        for fa in forges {
+            msg.push_str(&format!("mise use -g {fa}@latest\n"));
+        }
+        return Err(eyre!(msg.trim().to_string()));
     }
     let missing_plugins = tvs.iter().map(|tv| &tv.forge).collect::<HashSet<_>>();
     let mut missing_tools = ts
@@ -270,3 +385,78 @@ fn err_no_version_set(ts: Toolset, bin_name: &str, tvs: Vec<ToolVersion>) -> Res
         Err(eyre!(msg.trim().to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reshim_for() {
+        assert_cli!("install", "tiny@1", "dummy@1.0.0");
+        let config = Config::get();
+        let ts = ToolsetBuilder::new().build(&config).unwrap();
+        reshim(&ts).unwrap();
+        remove_all(dirs::SHIMS.join("rtx-tiny")).unwrap();
+        remove_all(dirs::SHIMS.join("dummy")).unwrap();
+        reshim_for(&ts, &["tiny".into()]).unwrap();
+        assert!(dirs::SHIMS.join("rtx-tiny").is_symlink());
+        assert!(!dirs::SHIMS.join("dummy").exists());
+        reshim(&ts).unwrap();
+        assert!(dirs::SHIMS.join("dummy").is_symlink());
+    }
+
+    #[test]
+    fn test_reshim_noop() {
+        assert_cli!("install", "tiny@1");
+        let config = Config::get();
+        let ts = ToolsetBuilder::new().build(&config).unwrap();
+        reshim(&ts).unwrap();
+        let mtime = |path: &Path| fs::symlink_metadata(path).unwrap().modified().unwrap();
+        let shim = dirs::SHIMS.join("rtx-tiny");
+        let (dir_mtime, shim_mtime) = (mtime(&dirs::SHIMS), mtime(&shim));
+        reshim(&ts).unwrap();
+        assert_eq!(mtime(&dirs::SHIMS), dir_mtime);
+        assert_eq!(mtime(&shim), shim_mtime);
+    }
+
+    #[test]
+    fn test_needs_reshim() {
+        assert_cli!("install", "tiny@1.0.1");
+        let config = Config::get();
+        let ts = ToolsetBuilder::new().build(&config).unwrap();
+        reshim(&ts).unwrap();
+        assert!(!ts.needs_reshim());
+
+        let bin = dirs::INSTALLS.join("tiny/1.0.1/bin/mise-test-needs-reshim");
+        file::write(&bin, "#!/bin/sh\n").unwrap();
+        file::make_executable(&bin).unwrap();
+        assert!(ts.needs_reshim());
+        reshim(&ts).unwrap();
+        assert!(!ts.needs_reshim());
+
+        file::remove_file(&bin).unwrap();
+        reshim(&ts).unwrap();
+    }
+
+    #[test]
+    fn test_reshim_removes_stale_shims() {
+        assert_cli!("install", "tiny@1.0.1");
+        let bin = dirs::INSTALLS.join("tiny/1.0.1/bin/mise-test-stale");
+        file::write(&bin, "#!/bin/sh\n").unwrap();
+        file::make_executable(&bin).unwrap();
+        let user_file = dirs::SHIMS.join("mise-test-user-file");
+        file::write(&user_file, "#!/bin/sh\n").unwrap();
+        file::make_executable(&user_file).unwrap();
+
+        let config = Config::get();
+        let ts = ToolsetBuilder::new().build(&config).unwrap();
+        reshim(&ts).unwrap();
+        assert!(dirs::SHIMS.join("mise-test-stale").is_symlink());
+
+        file::remove_file(&bin).unwrap();
+        reshim(&ts).unwrap();
+        assert!(!dirs::SHIMS.join("mise-test-stale").exists());
+        assert!(user_file.exists());
+        file::remove_file(&user_file).unwrap();
+    }
+}
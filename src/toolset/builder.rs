@@ -5,9 +5,24 @@ use itertools::Itertools;
 
 use crate::cli::args::{ForgeArg, ToolArg};
 use crate::config::{Config, Settings};
-use crate::toolset::{ToolRequest, ToolSource, Toolset};
+use crate::file::display_path;
+use crate::forge::ForgeType;
+use crate::toolset::{
+    Lockfile, MergeStrategy, ToolRequest, ToolRequestSet, ToolSource, ToolVersionOptions, Toolset,
+};
 use crate::{config, env};
 
+/// the kinds of sources a toolset is built from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolSourceKind {
+    /// global and system config files
+    GlobalConfig,
+    /// project-local config files
+    LocalConfig,
+    /// MISE_*_VERSION env vars
+    Environment,
+}
+
 #[derive(Debug, Default)]
 pub struct ToolsetBuilder {
     args: Vec<ToolArg>,
@@ -15,6 +30,8 @@ pub struct ToolsetBuilder {
     default_to_latest: bool,
     tool_filter: Option<HashSet<ForgeArg>>,
     installed_only: bool,
+    /// sources which are skipped with a warning instead of failing the build
+    best_effort: HashSet<ToolSourceKind>,
 }
 
 impl ToolsetBuilder {
@@ -47,6 +64,15 @@ impl ToolsetBuilder {
         self
     }
 
+    /// sources of this kind whose tools can't be read, e.g.: an invalid version, are skipped with a
+    /// warning and listed in Toolset::skipped_sources
+    /// this only covers reading the tools, a config file which doesn't parse still fails
+    /// Config::load before the builder runs
+    pub fn with_best_effort(mut self, kind: ToolSourceKind) -> Self {
+        self.best_effort.insert(kind);
+        self
+    }
+
     pub fn build(self, config: &Config) -> Result<Toolset> {
         let start_ms = std::time::Instant::now();
         let settings = Settings::try_get()?;
@@ -59,8 +85,12 @@ impl ToolsetBuilder {
         self.load_config_files(config, &mut toolset)?;
         self.load_runtime_env(&mut toolset, env::vars().collect())?;
         self.load_runtime_args(&mut toolset)?;
-        if let Err(err) = toolset.resolve() {
-            warn!("failed to resolve toolset: {err:#}");
+        apply_forge_default_options(&mut toolset, &settings.forge_default_options()?);
+        if let Some(path) = Lockfile::path(config) {
+            toolset.lockfile = Some(ToolRequestSet::from_lockfile(&path)?);
+        }
+        for (fa, err) in toolset.resolve_lenient() {
+            warn!("failed to resolve {fa}: {err:#}");
         }
 
         debug!("Toolset ({:?}): {toolset}", start_ms.elapsed());
@@ -68,12 +98,37 @@ impl ToolsetBuilder {
     }
 
     fn load_config_files(&self, config: &Config, ts: &mut Toolset) -> eyre::Result<()> {
+        let mut sets = vec![];
         for cf in config.config_files.values().rev() {
-            if self.global_only && !config::is_global_config(cf.get_path()) {
-                return Ok(());
+            let is_global = config::is_global_config(cf.get_path());
+            if self.global_only && !is_global {
+                break;
+            }
+            let kind = match is_global {
+                true => ToolSourceKind::GlobalConfig,
+                false => ToolSourceKind::LocalConfig,
+            };
+            match cf.to_tool_request_set() {
+                Ok(trs) => sets.push(trs),
+                Err(err) => self.skip_source(ts, kind, display_path(cf.get_path()), err)?,
             }
-            ts.merge(cf.to_toolset()?);
         }
+        ts.merge(Toolset::from_sets(sets), MergeStrategy::OverrideExisting);
+        Ok(())
+    }
+
+    fn skip_source(
+        &self,
+        ts: &mut Toolset,
+        kind: ToolSourceKind,
+        source: String,
+        err: eyre::Report,
+    ) -> eyre::Result<()> {
+        if !self.best_effort.contains(&kind) {
+            return Err(err);
+        }
+        warn!("skipping tools from {source}: {err:#}");
+        ts.skipped_sources.push(source);
         Ok(())
     }
 
@@ -96,13 +151,21 @@ impl ToolsetBuilder {
                     continue;
                 }
                 let fa: ForgeArg = plugin_name.as_str().into();
-                let source = ToolSource::Environment(k, v.clone());
+                let source = ToolSource::Environment(k.clone(), v.clone());
                 let mut env_ts = Toolset::new(source);
-                for v in v.split_whitespace() {
-                    let tvr = ToolRequest::new(fa.clone(), v)?;
-                    env_ts.add_version(tvr);
+                let requests = v
+                    .split_whitespace()
+                    .map(|v| ToolRequest::new(fa.clone(), v))
+                    .collect::<eyre::Result<Vec<_>>>();
+                match requests {
+                    Ok(requests) => {
+                        for tvr in requests {
+                            env_ts.add_version(tvr);
+                        }
+                        ts.merge(env_ts, MergeStrategy::OverrideExisting);
+                    }
+                    Err(err) => self.skip_source(ts, ToolSourceKind::Environment, k, err)?,
                 }
-                ts.merge(env_ts);
             }
         }
         Ok(())
@@ -112,8 +175,14 @@ impl ToolsetBuilder {
         if self.global_only {
             return Ok(());
         }
+        for fa in self.args.iter().map(|arg| &arg.forge).unique() {
+            if let Some(setting) = ts.disabled_by(fa) {
+                warn!("{fa} is disabled by {setting}, ignoring");
+            }
+        }
         for (_, args) in self.args.iter().into_group_map_by(|arg| arg.forge.clone()) {
             let mut arg_ts = Toolset::new(ToolSource::Argument);
+            let mut latest_ts = Toolset::new(ToolSource::Argument);
             for arg in args {
                 if let Some(tvr) = &arg.tvr {
                     arg_ts.add_version(tvr.clone());
@@ -121,21 +190,61 @@ impl ToolsetBuilder {
                     // this logic is required for `mise x` because with that specific command mise
                     // should default to installing the "latest" version if no version is specified
                     // in .mise.toml
-
-                    // determine if we already have some active version in config
-                    let set_as_latest = !ts
-                        .list_current_requests()
-                        .iter()
-                        .any(|tvr| tvr.forge() == &arg.forge);
-
-                    if set_as_latest {
-                        // no active version, so use "latest"
-                        arg_ts.add_version(ToolRequest::new(arg.forge.clone(), "latest")?);
-                    }
+                    latest_ts.add_version(ToolRequest::new(arg.forge.clone(), "latest")?);
                 }
             }
-            ts.merge(arg_ts);
+            ts.merge(arg_ts, MergeStrategy::OverrideExisting);
+            // only use "latest" if there is no active version in config
+            ts.merge(latest_ts, MergeStrategy::FillMissing);
         }
         Ok(())
     }
 }
+
+/// merges Settings::forge_default_options into every request, this happens before resolving since
+/// options can affect which version is resolved
+fn apply_forge_default_options(
+    ts: &mut Toolset,
+    defaults: &BTreeMap<ForgeType, ToolVersionOptions>,
+) {
+    for tvl in ts.versions.values_mut() {
+        if let Some(defaults) = defaults.get(&tvl.forge.forge_type) {
+            tvl.requests = tvl
+                .requests
+                .drain(..)
+                .map(|tvr| tvr.with_default_options(defaults))
+                .collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_effort_env() {
+        let env = BTreeMap::from([
+            ("MISE_DUMMY_VERSION".to_string(), "1".to_string()),
+            ("MISE_TINY_VERSION".to_string(), ">=oops".to_string()),
+        ]);
+        let mut ts = Toolset::default();
+        ToolsetBuilder::new()
+            .with_best_effort(ToolSourceKind::Environment)
+            .load_runtime_env(&mut ts, env.clone())
+            .unwrap();
+        assert_eq!(ts.skipped_sources, vec!["MISE_TINY_VERSION"]);
+        assert_eq!(ts.versions.keys().collect_vec(), vec![&"dummy".into()]);
+
+        // a source which isn't best-effort is required
+        let mut ts = Toolset::default();
+        let err = ToolsetBuilder::new()
+            .with_best_effort(ToolSourceKind::LocalConfig)
+            .load_runtime_env(&mut ts, env)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid version constraint >=oops"));
+        assert!(ts.skipped_sources.is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use itertools::Itertools;
+
+use crate::toolset::{ToolRequest, ToolVersion};
+
+/// what Toolset::install_versions_with_summary did, e.g.:
+/// "dummy: 1 installed 1 skipped, tiny: 2 installed in 1.23s"
+#[derive(Debug, Default)]
+pub struct InstallSummary {
+    /// versions which were newly installed
+    pub installed: Vec<ToolVersion>,
+    /// versions which were already installed
+    pub skipped: Vec<ToolVersion>,
+    /// with InstallOptions::keep_going, the requests which failed to install
+    pub failed: Vec<ToolRequest>,
+    /// wall time of the whole install
+    pub duration: Duration,
+    /// the installed and skipped versions with what installing each took
+    pub records: Vec<InstallRecord>,
+}
+
+/// metadata of installing a single version
+#[derive(Debug, Clone)]
+pub struct InstallRecord {
+    pub tv: ToolVersion,
+    /// what the forge reported downloading, see InstallContext::add_download
+    pub bytes: Option<u64>,
+    /// including retries
+    pub duration: Duration,
+}
+
+impl InstallSummary {
+    /// (installed, skipped, failed) counts of each forge
+    pub fn by_forge(&self) -> IndexMap<String, (usize, usize, usize)> {
+        let mut by_forge: IndexMap<String, (usize, usize, usize)> = IndexMap::new();
+        for tv in &self.installed {
+            by_forge.entry(tv.forge.id.clone()).or_default().0 += 1;
+        }
+        for tv in &self.skipped {
+            by_forge.entry(tv.forge.id.clone()).or_default().1 += 1;
+        }
+        for tr in &self.failed {
+            by_forge.entry(tr.forge().id.clone()).or_default().2 += 1;
+        }
+        by_forge.sort_keys();
+        by_forge
+    }
+
+    pub fn total(&self) -> usize {
+        self.installed.len() + self.skipped.len() + self.failed.len()
+    }
+}
+
+impl Display for InstallSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let forges = self
+            .by_forge()
+            .into_iter()
+            .map(|(id, (installed, skipped, failed))| {
+                let counts = [
+                    (installed, "installed"),
+                    (skipped, "skipped"),
+                    (failed, "failed"),
+                ]
+                .into_iter()
+                .filter(|(n, _)| *n > 0)
+                .map(|(n, what)| format!("{n} {what}"))
+                .join(" ");
+                format!("{id}: {counts}")
+            })
+            .join(", ");
+        write!(f, "{forges} in {:.2?}", self.duration)
+    }
+}
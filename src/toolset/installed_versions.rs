@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use eyre::Result;
+use once_cell::sync::Lazy;
+
+use crate::forge::Forge;
+
+/// the cache used by Toolset, invalidated by Forge::install_version and Forge::uninstall_version
+pub static INSTALLED_VERSIONS: Lazy<InstalledVersionsCache> = Lazy::new(Default::default);
+
+/// process-lifetime cache of Forge::list_installed_versions so commands which look up bins of
+/// several tools don't scan the installs dirs over and over
+/// entries are keyed by forge id and the mtime of its installs dir so versions installed or linked
+/// by another process are picked up
+#[derive(Debug, Default)]
+pub struct InstalledVersionsCache {
+    entries: Mutex<HashMap<String, (Option<SystemTime>, Vec<String>)>>,
+}
+
+impl InstalledVersionsCache {
+    pub fn get(&self, p: &dyn Forge) -> Result<Vec<String>> {
+        let mtime = p
+            .fa()
+            .installs_path
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok();
+        if let Some((cached_mtime, versions)) = self.entries.lock().unwrap().get(p.id()) {
+            if *cached_mtime == mtime {
+                return Ok(versions.clone());
+            }
+        }
+        // the lock isn't held while scanning so other tools can be looked up in parallel
+        let versions = p.list_installed_versions()?;
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(p.id().to_string(), (mtime, versions.clone()));
+        Ok(versions)
+    }
+
+    /// create_install_dirs changes the mtime of the installs dir when an install starts, but the
+    /// version only counts as installed once its incomplete file in the cache dir is removed at
+    /// the end, which doesn't change it, so this needs to be called after installing
+    pub fn invalidate(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use crate::forge::mock::MockForge;
+
+    use super::*;
+
+    #[test]
+    fn test_installed_versions_cache() {
+        let cache = InstalledVersionsCache::default();
+        let forge = MockForge::new("mock-installed").with_installed_versions(&["1.0.0"]);
+        assert_eq!(cache.get(&forge).unwrap(), vec!["1.0.0"]);
+        assert_eq!(cache.get(&forge).unwrap(), vec!["1.0.0"]);
+        assert_eq!(cache.get(&forge).unwrap(), vec!["1.0.0"]);
+        assert_eq!(forge.installed_calls.load(Ordering::Relaxed), 1);
+        cache.invalidate();
+        cache.get(&forge).unwrap();
+        assert_eq!(forge.installed_calls.load(Ordering::Relaxed), 2);
+    }
+}
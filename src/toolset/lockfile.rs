@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::file;
+
+/// mise.lock in the project root, e.g.:
+///
+/// ```toml
+/// [tools]
+/// node = ["20.12.2"]
+/// "cargo:eza" = ["0.18.13"]
+/// ```
+///
+/// an empty mise.lock can be created to opt in, `mise install` fills in the resolved versions
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Lockfile {
+    /// forge id -> resolved versions, in the same order as the requests in config
+    #[serde(default)]
+    pub tools: BTreeMap<String, Vec<String>>,
+}
+
+impl Lockfile {
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let body = file::read_to_string(path)?;
+        Ok(toml::from_str(&body)?)
+    }
+
+    pub fn write(&self, path: &Path) -> eyre::Result<()> {
+        file::write(path, toml::to_string_pretty(self)?)
+    }
+
+    /// mise.lock in the project root if it exists
+    pub fn path(config: &Config) -> Option<PathBuf> {
+        let path = config.project_root.as_ref()?.join("mise.lock");
+        path.is_file().then_some(path)
+    }
+}
@@ -1,49 +1,100 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
-use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::env::split_paths;
+use std::ffi::OsString;
+use std::fmt::{Debug, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{panic, thread};
 
-use console::truncate_str;
+use console::{style, truncate_str};
 use eyre::Result;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use indexmap::IndexMap;
 use itertools::Itertools;
 use rayon::prelude::*;
+use versions::Versioning;
 
-pub use builder::ToolsetBuilder;
+pub use builder::{ToolSourceKind, ToolsetBuilder};
+pub use install_summary::{InstallRecord, InstallSummary};
+pub use installed_versions::INSTALLED_VERSIONS;
+pub use lockfile::Lockfile;
+pub use outdated_info::{BumpKind, OutdatedInfo, UpdateEntry};
+pub use path_issue::PathIssue;
 pub use tool_request_set::{ToolRequestSet, ToolRequestSetBuilder};
 pub use tool_source::ToolSource;
-pub use tool_version::ToolVersion;
+pub use tool_version::{find_bin, ToolVersion};
 pub use tool_version_list::ToolVersionList;
 pub use tool_version_request::ToolRequest;
+pub use toolset_diff::ToolsetDiff;
 
+use crate::cache::CacheManager;
 use crate::cli::args::ForgeArg;
-use crate::config::settings::SettingsStatusMissingTools;
+use crate::config::settings::{SettingsStatus, SettingsStatusMissingTools};
 use crate::config::{Config, Settings};
 use crate::env::TERM_WIDTH;
-use crate::forge::Forge;
-use crate::install_context::InstallContext;
+use crate::env_diff::EnvDiff;
+use crate::errors::{Error, ResolveError};
+use crate::forge::{AForge, Forge, ForgeType};
+use crate::hash::hash_to_str;
+use crate::install_context::{DownloadBudget, InstallContext};
 use crate::path_env::PathEnv;
+use crate::toolset::which_cache::WhichCache;
+use crate::ui::ctrlc;
 use crate::ui::multi_progress_report::MultiProgressReport;
-use crate::{env, forge, runtime_symlinks, shims};
+use crate::ui::progress_report::SingleReport;
+use crate::ui::prompt;
+use crate::{dirs, env, file, forge, runtime_symlinks, shims};
 
 mod builder;
+mod install_summary;
+mod installed_versions;
+mod lockfile;
+mod outdated_info;
+mod path_issue;
 mod tool_request_set;
 mod tool_source;
 mod tool_version;
 mod tool_version_list;
 mod tool_version_request;
+mod toolset_diff;
+mod which_cache;
 
 pub type ToolVersionOptions = BTreeMap<String, String>;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct InstallOptions {
     pub force: bool,
     pub jobs: Option<usize>,
     pub raw: bool,
     pub latest_versions: bool,
+    /// defaults to the install_timeout setting
+    pub timeout: Option<Duration>,
+    /// number of times to retry a failed install
+    pub retries: usize,
+    /// in install_arg_versions, install the latest version of tools which are outdated
+    pub reinstall_if_outdated: bool,
+    /// don't reshim after installing, call Toolset::reshim once done with all batches
+    pub skip_reshim: bool,
+    /// don't rebuild runtime symlinks after installing
+    pub skip_runtime_symlinks: bool,
+    /// skip prereleases when resolving versions unless there is no stable version, defaults to
+    /// the prefer_stable setting
+    pub prefer_stable: bool,
+    /// resolve and print the versions which would be installed without installing them
+    pub dry_run: bool,
+    /// in install_arg_versions, return the exact versions of the argument tools to persist
+    pub pin: bool,
+    /// continue installing the other tools if one fails, the failures are returned together
+    /// as Error::InstallFailed once everything else is installed
+    pub keep_going: bool,
+    /// never touch the network, versions are resolved from the cached remote versions and
+    /// forges only use already-downloaded artifacts, defaults to the offline setting
+    pub offline: bool,
+    /// set on ctrl-c, workers stop picking up tools and retries stop waiting once it is set
+    pub cancel: Arc<AtomicBool>,
 }
 
 impl InstallOptions {
@@ -52,23 +103,157 @@ impl InstallOptions {
         InstallOptions {
             jobs: Some(settings.jobs),
             raw: settings.raw,
+            retries: settings.install_retries,
+            prefer_stable: settings.prefer_stable,
+            offline: settings.offline,
             ..Default::default()
         }
     }
 }
 
+/// options for resolve_request
+#[derive(Debug, Clone)]
+pub struct ResolveOptions {
+    /// resolve to the latest remote version even if a matching version is installed
+    pub latest_versions: bool,
+    /// skip prereleases unless there is no stable version matching the request
+    pub prefer_stable: bool,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            latest_versions: false,
+            prefer_stable: Settings::get().prefer_stable,
+        }
+    }
+}
+
+/// see install_chain
+const MAX_INSTALL_DEPTH: usize = 50;
+
+/// how Toolset::merge handles tools which are in both toolsets
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// tools from the other toolset replace existing ones, e.g.: local config over global config
+    #[default]
+    OverrideExisting,
+    /// only tools which are not already in the toolset are added
+    FillMissing,
+}
+
+/// "3/10 tools installed" summary shared by the install workers
+/// leaf dependencies are installed first then skipped in the main pass so they're only counted once
+/// with InstallOptions::keep_going it also collects the failed installs
+struct InstallProgress {
+    pr: Option<Box<dyn SingleReport>>,
+    total: usize,
+    installed: Mutex<HashSet<String>>,
+    failed: Mutex<Vec<(ToolRequest, eyre::Report)>>,
+    summary: Mutex<InstallSummary>,
+    downloads: DownloadBudget,
+}
+
+impl InstallProgress {
+    fn new(mpr: &MultiProgressReport, total: usize) -> Self {
+        let pr = match total > 1 {
+            true => Some(mpr.add("install")),
+            false => None,
+        };
+        let progress = Self {
+            pr,
+            total,
+            installed: Mutex::new(HashSet::new()),
+            failed: Mutex::new(vec![]),
+            summary: Mutex::new(InstallSummary::default()),
+            downloads: DownloadBudget::new(Settings::get().max_download_size),
+        };
+        progress.set_message(0);
+        progress
+    }
+    /// skipped is true if the version was installed before this install started
+    fn installed(&self, tv: &ToolVersion, skipped: bool) {
+        let mut installed = self.installed.lock().unwrap();
+        if installed.insert(tv.to_string()) {
+            self.set_message(installed.len());
+            let mut summary = self.summary.lock().unwrap();
+            match skipped {
+                true => summary.skipped.push(tv.clone()),
+                false => summary.installed.push(tv.clone()),
+            }
+        }
+    }
+    fn failed(&self, tr: &ToolRequest, err: eyre::Report) {
+        let mut failed = self.failed.lock().unwrap();
+        // a failed leaf dependency is attempted again in the main pass
+        if !failed.iter().any(|(f, _)| f == tr) {
+            failed.push((tr.clone(), err));
+        }
+    }
+    fn set_message(&self, installed: usize) {
+        if let Some(pr) = &self.pr {
+            pr.set_message(format!("{installed}/{} tools installed", self.total));
+        }
+    }
+    fn finish(&self) {
+        if let Some(pr) = &self.pr {
+            pr.finish();
+        }
+    }
+}
+
+/// what set an env var, see Toolset::env_with_sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvSource {
+    /// the exec-env or env_* options of a tool
+    Tool(ForgeArg),
+    /// the env of the config files
+    Config,
+}
+
+impl Display for EnvSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tool(fa) => write!(f, "{fa}"),
+            Self::Config => write!(f, "config"),
+        }
+    }
+}
+
+/// result of Toolset::which_with_install_hint
+#[derive(Debug)]
+pub enum WhichResult {
+    /// an installed version provides the bin
+    Found(Arc<dyn Forge>, ToolVersion),
+    /// the bin is likely provided by these requested versions which are not installed
+    NotInstalled(Vec<ToolVersion>),
+    NotFound,
+}
+
+#[derive(Debug, Default)]
+pub struct PruneOptions {
+    pub dry_run: bool,
+    /// only prune versions of these tools
+    pub forges: Option<Vec<ForgeArg>>,
+}
+
 /// a toolset is a collection of tools for various plugins
 ///
 /// one example is a .tool-versions file
 /// the idea is that we start with an empty toolset, then
 /// merge in other toolsets from various sources
-#[derive(Debug, Default, Clone)]
+#[derive(Default, Clone)]
 pub struct Toolset {
     pub versions: IndexMap<ForgeArg, ToolVersionList>,
     pub source: Option<ToolSource>,
     pub disable_tools: HashSet<ForgeArg>,
     pub tool_filter: Option<HashSet<ForgeArg>>,
     pub installed_only: bool,
+    /// best-effort sources which failed to load and were skipped
+    pub skipped_sources: Vec<String>,
+    /// exact versions from mise.lock which are preferred when resolving
+    pub lockfile: Option<ToolRequestSet>,
+    which_cache: WhichCache,
 }
 
 impl Toolset {
@@ -78,62 +263,190 @@ impl Toolset {
             ..Default::default()
         }
     }
-    pub fn add_version(&mut self, tvr: ToolRequest) {
+    /// returns false if the request was ignored because the tool is disabled, see disabled_by
+    pub fn add_version(&mut self, tvr: ToolRequest) -> bool {
         let fa = tvr.forge();
-        if self.is_disabled(fa) {
-            return;
+        if let Some(setting) = self.disabled_by(fa) {
+            debug!("ignoring {tvr}: {fa} is disabled by {setting}");
+            return false;
         }
         let tvl = self
             .versions
             .entry(tvr.forge().clone())
             .or_insert_with(|| ToolVersionList::new(fa.clone(), self.source.clone().unwrap()));
         tvl.requests.push(tvr);
+        self.which_cache.invalidate();
+        true
     }
-    pub fn merge(&mut self, other: Toolset) {
-        let mut versions = other.versions;
-        for (plugin, tvl) in self.versions.clone() {
+    pub fn merge(&mut self, other: Toolset, strategy: MergeStrategy) {
+        let (mut versions, backfill) = match strategy {
+            MergeStrategy::OverrideExisting => (other.versions, self.versions.clone()),
+            MergeStrategy::FillMissing => (self.versions.clone(), other.versions),
+        };
+        for (plugin, tvl) in backfill {
             if !versions.contains_key(&plugin) {
                 versions.insert(plugin, tvl);
             }
         }
         versions.retain(|_, tvl| !self.is_disabled(&tvl.forge));
         self.versions = versions;
-        self.source = other.source;
+        self.which_cache.invalidate();
+        if strategy == MergeStrategy::OverrideExisting || self.source.is_none() {
+            self.source = other.source;
+        }
+    }
+    /// merges request sets in precedence order, a later set's requests replace those of an earlier
+    /// set for the same tool and each tool keeps the source of the set it came from
+    pub fn from_sets(sets: Vec<ToolRequestSet>) -> Self {
+        sets.into_iter().fold(Self::default(), |mut ts, trs| {
+            ts.merge(trs.into(), MergeStrategy::OverrideExisting);
+            ts
+        })
+    }
+    /// adds the requests of a ToolRequestSet replacing existing requests for the same tools
+    /// each tool keeps the source it came from, e.g.: for the merged request set of all config files
+    pub fn merge_request_set(&mut self, trs: ToolRequestSet) {
+        let ToolRequestSet { tools, mut sources } = trs;
+        for (fa, requests) in tools {
+            if self.is_disabled(&fa) {
+                continue;
+            }
+            let Some(source) = sources.remove(&fa).or_else(|| self.source.clone()) else {
+                warn!("no source for {fa}, ignoring");
+                continue;
+            };
+            let mut tvl = ToolVersionList::new(fa.clone(), source);
+            tvl.requests = requests;
+            self.versions.insert(fa, tvl);
+        }
+        self.which_cache.invalidate();
+    }
+    /// the tools and version requests which changed from self to other, resolved versions are
+    /// not compared
+    pub fn diff(&self, other: &Toolset) -> ToolsetDiff {
+        ToolsetDiff::new(self, other)
     }
+    /// resolves all versions, failures are collected into Error::ResolveFailed so callers can
+    /// match on the ResolveError of each tool
     pub fn resolve(&mut self) -> eyre::Result<()> {
+        let errors = self.resolve_each();
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(Error::ResolveFailed(errors).into()),
+        }
+    }
+    /// like resolve but the tools which did resolve can be used even if others failed, e.g.: so
+    /// `mise env` still works with one broken tool
+    pub fn resolve_lenient(&mut self) -> Vec<(ForgeArg, eyre::Report)> {
+        self.resolve_each()
+            .into_iter()
+            .map(|(fa, err)| (fa, err.into()))
+            .collect()
+    }
+    /// every tool is resolved independently so a failure leaves the others resolved
+    fn resolve_each(&mut self) -> Vec<(ForgeArg, ResolveError)> {
         self.list_missing_plugins();
-        let errors = self
-            .versions
+        self.which_cache.invalidate();
+        self.versions
             .iter_mut()
             .collect::<Vec<_>>()
             .par_iter_mut()
-            .map(|(_, v)| v.resolve(false))
-            .filter(|r| r.is_err())
-            .map(|r| r.unwrap_err())
-            .collect::<Vec<_>>();
-        match errors.is_empty() {
-            true => Ok(()),
-            false => {
-                let err = eyre!("error resolving versions");
-                Err(errors.into_iter().fold(err, |e, x| e.wrap_err(x)))
-            }
+            .filter_map(|(fa, v)| {
+                let err = v.resolve(false, self.lockfile.as_ref()).err()?;
+                let is_installed = forge::get(fa).is_installed();
+                Some(((*fa).clone(), ResolveError::new(fa, is_installed, err)))
+            })
+            .collect()
+    }
+    /// writes the resolved versions of all tools to a lockfile, only versions and prefixes are
+    /// written since those are the only requests which are locked, see ToolRequestSet::locked
+    pub fn write_lockfile(&self, path: &Path) -> Result<()> {
+        let tools = self
+            .versions
+            .iter()
+            .map(|(fa, tvl)| {
+                let versions = tvl
+                    .versions
+                    .iter()
+                    .filter(|tv| {
+                        matches!(
+                            tv.request,
+                            ToolRequest::Version { .. } | ToolRequest::Prefix { .. }
+                        )
+                    })
+                    .map(|tv| tv.version.clone())
+                    .collect_vec();
+                (fa.to_string(), versions)
+            })
+            .filter(|(_, versions)| !versions.is_empty())
+            .collect();
+        Lockfile { tools }.write(path)
+    }
+    /// the remote versions of any kind of forge sorted newest first
+    /// cached in the forge's cache_path for MISE_FETCH_REMOTE_VERSIONS_CACHE unless refresh is set
+    /// with the offline setting only the cached versions are returned
+    pub fn list_remote_versions(&self, fa: &ForgeArg, refresh: bool) -> Result<Vec<String>> {
+        let settings = Settings::get();
+        list_remote_versions(forge::get(fa).as_ref(), refresh, settings.offline)
+    }
+    /// re-resolves the versions of one tool, e.g.: after it was installed
+    pub fn resolve_single(&mut self, fa: &ForgeArg) -> eyre::Result<()> {
+        self.which_cache.invalidate();
+        match self.versions.get_mut(fa) {
+            Some(tvl) => tvl.resolve(false, self.lockfile.as_ref()),
+            None => Ok(()),
         }
     }
+    /// installs the tools which were passed as arguments, returns the installed versions and
+    /// with InstallOptions::pin the exact versions of all argument tools so they can be persisted
     pub fn install_arg_versions(
         &mut self,
         config: &Config,
         opts: &InstallOptions,
-    ) -> Result<Vec<ToolVersion>> {
+    ) -> Result<(Vec<ToolVersion>, Vec<(ForgeArg, String)>)> {
         let mpr = MultiProgressReport::get();
         let versions = self
             .list_current_versions()
             .into_iter()
-            .filter(|(p, tv)| opts.force || !p.is_version_installed(tv))
+            .filter(|(p, tv)| {
+                opts.force
+                    || !p.is_version_installed(tv)
+                    || (opts.reinstall_if_outdated && is_outdated(p.as_ref(), tv))
+            })
             .map(|(_, tv)| tv)
             .filter(|tv| matches!(self.versions[&tv.forge].source, ToolSource::Argument))
             .map(|tv| tv.request)
             .collect_vec();
-        self.install_versions(config, versions, &mpr, opts)
+        let opts = InstallOptions {
+            latest_versions: opts.latest_versions || opts.reinstall_if_outdated,
+            ..opts.clone()
+        };
+        let installed = self.install_versions(config, versions, &mpr, &opts)?;
+        let pinned = match opts.pin {
+            true => self.pin_arg_versions(&installed),
+            false => vec![],
+        };
+        Ok((installed, pinned))
+    }
+
+    /// the exact versions of the tools which were passed as arguments, "system" stays as-is
+    /// the versions which were just installed are used as they were resolved for the install, the
+    /// others are already installed so their resolved version is
+    fn pin_arg_versions(&self, installed: &[ToolVersion]) -> Vec<(ForgeArg, String)> {
+        self.list_current_versions()
+            .into_iter()
+            .filter(|(p, _)| matches!(self.versions[p.fa()].source, ToolSource::Argument))
+            .map(|(_, tv)| {
+                let version = match tv.request {
+                    ToolRequest::System(_) => tv.request.version(),
+                    _ => installed
+                        .iter()
+                        .find(|installed| installed.request == tv.request)
+                        .map_or(tv.version, |installed| installed.version.clone()),
+                };
+                (tv.forge, version)
+            })
+            .collect()
     }
 
     pub fn list_missing_plugins(&self) -> Vec<String> {
@@ -152,13 +465,125 @@ impl Toolset {
         mpr: &MultiProgressReport,
         opts: &InstallOptions,
     ) -> Result<Vec<ToolVersion>> {
+        let mut summary = InstallSummary::default();
+        self.install_versions_with_summary(config, versions, mpr, opts, &mut summary)
+    }
+
+    /// like install_versions but also fills in which versions were installed, skipped, or failed
+    /// and how long it took, the summary is populated even if installing fails
+    pub fn install_versions_with_summary(
+        &mut self,
+        config: &Config,
+        versions: Vec<ToolRequest>,
+        mpr: &MultiProgressReport,
+        opts: &InstallOptions,
+        summary: &mut InstallSummary,
+    ) -> Result<Vec<ToolVersion>> {
+        let start = Instant::now();
+        if versions.is_empty() {
+            return Ok(vec![]);
+        }
+        let order = installation_order(&versions)?;
+        debug!(
+            "install order: {}",
+            order
+                .iter()
+                .map(|round| round.iter().join(", "))
+                .join(" -> ")
+        );
+        if opts.dry_run {
+            return self.install_plan(&versions, &order, opts);
+        }
+        let _ctrlc = ctrlc::cancel_on_ctrlc(opts.cancel.clone())?;
+        let progress = InstallProgress::new(mpr, versions.len());
+        let installed =
+            self.install_versions_with_progress(config, versions, mpr, opts, &progress, &[]);
+        progress.finish();
+        let failed = progress.failed.into_inner().unwrap();
+        *summary = progress.summary.into_inner().unwrap();
+        summary.failed = failed.iter().map(|(tr, _)| tr.clone()).collect();
+        summary.duration = start.elapsed();
+        let installed = installed.map(|records| {
+            summary.records.clone_from(&records);
+            records.into_iter().map(|r| r.tv).collect_vec()
+        });
+        match installed {
+            Ok(installed) if !failed.is_empty() => {
+                Err(Error::InstallFailed { installed, failed }.into())
+            }
+            installed => installed,
+        }
+    }
+
+    /// resolves the versions which install_versions would install in installation order
+    fn install_plan(
+        &self,
+        versions: &[ToolRequest],
+        order: &[Vec<ForgeArg>],
+        opts: &InstallOptions,
+    ) -> Result<Vec<ToolVersion>> {
+        let dependencies = versions
+            .iter()
+            .map(|tr| Ok((tr.forge(), tr.dependencies()?)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut plan = vec![];
+        for fa in order.iter().flatten() {
+            let dependents = dependencies
+                .iter()
+                .filter(|(_, deps)| deps.contains(fa))
+                .map(|(dependent, _)| dependent)
+                .unique()
+                .join(", ");
+            let p = forge::get(fa);
+            for tr in versions.iter().filter(|tr| tr.forge() == fa) {
+                let tv = resolve_for_install(p.as_ref(), tr.clone(), opts)?;
+                match dependents.is_empty() {
+                    true => info!("would install {tv}"),
+                    false => info!("would install {tv} (dependency of {dependents})"),
+                }
+                plan.push(tv);
+            }
+        }
+        Ok(plan)
+    }
+
+    fn install_versions_with_progress(
+        &mut self,
+        config: &Config,
+        versions: Vec<ToolRequest>,
+        mpr: &MultiProgressReport,
+        opts: &InstallOptions,
+        progress: &InstallProgress,
+        chain: &[String],
+    ) -> Result<Vec<InstallRecord>> {
         if versions.is_empty() {
             return Ok(vec![]);
         }
+        let chain = install_chain(chain, &versions, MAX_INSTALL_DEPTH)?;
+        check_dependency_cycles(&versions, |tr| tr.dependencies())?;
         let leaf_deps = get_leaf_dependencies(&versions)?;
         if leaf_deps.len() < versions.len() {
             debug!("installing {} leaf tools first", leaf_deps.len());
-            self.install_versions(config, leaf_deps.into_iter().cloned().collect(), mpr, opts)?;
+            let leaf_opts = InstallOptions {
+                skip_reshim: true,
+                skip_runtime_symlinks: true,
+                ..opts.clone()
+            };
+            let leaf_deps: Vec<_> = leaf_deps.into_iter().cloned().collect();
+            // a separate line so the dependency pass is distinguishable from the requested tools
+            let pr = mpr.add("dependencies");
+            pr.set_message(format!(
+                "installing dependencies: {}",
+                leaf_deps.iter().map(|tr| tr.forge()).join(", ")
+            ));
+            let result = self.install_versions_with_progress(
+                config, leaf_deps, mpr, &leaf_opts, progress, &chain,
+            );
+            match &result {
+                Ok(_) => pr.finish_with_message("installed dependencies".into()),
+                Err(_) => pr.finish_with_message("failed to install dependencies".into()),
+            }
+            result?;
         }
         let settings = Settings::try_get()?;
         let queue: Vec<_> = versions
@@ -166,54 +591,161 @@ impl Toolset {
             .rev()
             .group_by(|v| v.forge().clone())
             .into_iter()
-            .map(|(fa, v)| (forge::get(&fa), v.collect_vec()))
+            .map(|(fa, v)| {
+                let v = v.collect_vec();
+                // the `repo` option overrides where a plugin is cloned from
+                let source_url = v.iter().find_map(|tr| tr.options().get("repo").cloned());
+                let fa = match source_url {
+                    Some(url) if fa.source_url.is_none() => ForgeArg {
+                        source_url: Some(url),
+                        ..fa
+                    },
+                    _ => fa,
+                };
+                (forge::get(&fa), v)
+            })
             .collect();
         for (t, _) in &queue {
             if !t.is_installed() {
+                ensure!(!opts.offline, "required the {t} plugin but offline");
                 t.ensure_installed(mpr, false)?;
             }
         }
+        let resolved = resolve_latest_for_install(&queue, opts);
         let queue = Arc::new(Mutex::new(queue));
         let raw = opts.raw || settings.raw;
         let jobs = match raw {
             true => 1,
             false => opts.jobs.unwrap_or(settings.jobs),
         };
-        let installing: HashSet<String> = HashSet::new();
+        let timeout = match opts.timeout {
+            Some(timeout) => Some(timeout),
+            None => settings.install_timeout()?,
+        };
+        let forge_jobs = settings.forge_jobs()?;
+        let installing: HashMap<String, ForgeType> = HashMap::new();
         let installing = Arc::new(Mutex::new(installing));
         let installed = thread::scope(|s| {
             #[allow(clippy::map_collect_result_unit)]
             (0..jobs)
-                .map(|_| {
+                .map(|worker| {
                     let queue = queue.clone();
                     let installing = installing.clone();
+                    let forge_jobs = &forge_jobs;
+                    let resolved = &resolved;
                     let ts = &*self;
                     s.spawn(move || {
-                        let next_job = || queue.lock().unwrap().pop();
+                        // the locks are released before logging so other workers aren't held up
+                        let next_job = || loop {
+                            if opts.cancel.load(Ordering::Relaxed) {
+                                trace!("worker {worker}: install cancelled");
+                                return None;
+                            }
+                            let mut queue = queue.lock().unwrap();
+                            if queue.is_empty() {
+                                drop(queue);
+                                trace!("worker {worker}: install queue is empty");
+                                return None;
+                            }
+                            let mut installing = installing.lock().unwrap();
+                            let job = pick_job(&mut queue, &mut installing, forge_jobs);
+                            let remaining = queue.len();
+                            drop(installing);
+                            drop(queue);
+                            if let Some((t, versions)) = job {
+                                trace!(
+                                    "worker {worker}: picked up {t} ({} versions), {remaining} queued",
+                                    versions.len()
+                                );
+                                return Some((t, versions));
+                            }
+                            trace!("queued tools are at their forge job limit, waiting");
+                            sleep(Duration::from_millis(100));
+                        };
+                        let install_one = |t: &AForge,
+                                           tv: ToolRequest|
+                         -> Result<(InstallRecord, bool)> {
+                            // TODO: this logic should be able to be removed now I think
+                            for dep in t.get_dependencies(&tv)? {
+                                while installing.lock().unwrap().contains_key(&dep.to_string()) {
+                                    trace!(
+                                        "{tv} waiting for dependency {dep} to finish installing"
+                                    );
+                                    sleep(Duration::from_millis(100));
+                                }
+                            }
+                            let tv = match resolved.get(&tv) {
+                                Some(resolved) => resolved.clone(),
+                                None => resolve_for_install(t.as_ref(), tv, opts)?,
+                            };
+                            let skipped = !opts.force && t.is_version_installed(&tv);
+                            let start = Instant::now();
+                            let mut attempt = 0;
+                            // the progress bar is shared by the attempts so retries don't leave
+                            // one behind for each failed attempt
+                            let mut ctx = InstallContext {
+                                ts,
+                                pr: mpr.add(&tv.style()),
+                                tv: tv.clone(),
+                                force: opts.force,
+                                offline: opts.offline,
+                                downloads: &progress.downloads,
+                                deadline: None,
+                            };
+                            loop {
+                                // every attempt gets the full timeout
+                                ctx.deadline = timeout.map(|timeout| Instant::now() + timeout);
+                                match install_version(t.as_ref(), &ctx) {
+                                    Ok(()) => {
+                                        let record = InstallRecord {
+                                            bytes: progress.downloads.downloaded(&tv),
+                                            duration: start.elapsed(),
+                                            tv,
+                                        };
+                                        return Ok((record, skipped));
+                                    }
+                                    Err(err) if attempt < opts.retries => {
+                                        attempt += 1;
+                                        warn!(
+                                            "retrying {tv} ({attempt}/{}): {err:#}",
+                                            opts.retries
+                                        );
+                                        ctx.pr.set_message(format!(
+                                            "retrying ({attempt}/{})",
+                                            opts.retries
+                                        ));
+                                        backoff(attempt, &opts.cancel)?;
+                                    }
+                                    Err(err) => return Err(err),
+                                }
+                            }
+                        };
                         let mut installed = vec![];
                         while let Some((t, versions)) = next_job() {
-                            installing.lock().unwrap().insert(t.id().into());
                             for tv in versions {
-                                // TODO: this logic should be able to be removed now I think
-                                for dep in t.get_dependencies(&tv)? {
-                                    while installing.lock().unwrap().contains(&dep.to_string()) {
-                                        trace!(
-                                        "{tv} waiting for dependency {dep} to finish installing"
-                                    );
-                                        sleep(Duration::from_millis(100));
+                                if opts.cancel.load(Ordering::Relaxed) {
+                                    break;
+                                }
+                                match catch_install_panic(&tv, || install_one(&t, tv.clone())) {
+                                    Ok(Ok((record, skipped))) => {
+                                        progress.installed(&record.tv, skipped);
+                                        installed.push(record);
+                                    }
+                                    Ok(Err(err)) if opts.keep_going => {
+                                        warn!("failed to install {tv}: {err:#}");
+                                        progress.failed(&tv, err);
+                                    }
+                                    Ok(Err(err)) => return Err(err),
+                                    // a bug in one forge shouldn't abort the other installs
+                                    Err(err) => {
+                                        warn!("{err:#}");
+                                        progress.failed(&tv, err);
                                     }
                                 }
-                                let tv = tv.resolve(t.as_ref(), opts.latest_versions)?;
-                                let ctx = InstallContext {
-                                    ts,
-                                    pr: mpr.add(&tv.style()),
-                                    tv: tv.clone(),
-                                    force: opts.force,
-                                };
-                                t.install_version(ctx)?;
-                                installed.push(tv);
                             }
                             installing.lock().unwrap().remove(t.id());
+                            let remaining = queue.lock().unwrap().len();
+                            trace!("worker {worker}: finished {t}, {remaining} queued");
                         }
                         Ok(installed)
                     })
@@ -224,17 +756,52 @@ impl Toolset {
                     Ok(x) => x,
                     Err(e) => panic::resume_unwind(e),
                 })
-                .collect::<Result<Vec<Vec<ToolVersion>>>>()
+                .collect::<Result<Vec<Vec<InstallRecord>>>>()
                 .map(|x| x.into_iter().flatten().collect())
         })?;
-        if let Err(err) = self.resolve() {
-            debug!("error resolving versions after install: {err:#}");
+        let installed_forges = installed
+            .iter()
+            .map(|r| r.tv.forge.clone())
+            .unique()
+            .collect_vec();
+        for fa in &installed_forges {
+            if let Err(err) = self.resolve_single(fa) {
+                debug!("error resolving versions after install: {err:#}");
+            }
+        }
+        // newly installed versions can provide bins which were cached as missing
+        self.which_cache.invalidate();
+        if !opts.skip_reshim {
+            // only the installed tools can have new shims
+            shims::reshim_for(self, &installed_forges)?;
+        }
+        if !opts.skip_runtime_symlinks {
+            runtime_symlinks::rebuild(config)?;
+        }
+        if !installed.is_empty() {
+            invalidate_env_cache()?;
+        }
+        // checked after reshimming so the tools which did finish can be used
+        if opts.cancel.load(Ordering::Relaxed) {
+            bail!(
+                "install cancelled after installing {} tools",
+                installed.len()
+            );
         }
-        shims::reshim(self)?;
-        runtime_symlinks::rebuild(config)?;
         Ok(installed)
     }
 
+    pub fn reshim(&self) -> Result<()> {
+        shims::reshim(self)
+    }
+    /// whether the shims dir is out of date, callers can skip reshim when it isn't
+    pub fn needs_reshim(&self) -> bool {
+        shims::needs_reshim(self).unwrap_or_else(|e| {
+            warn!("Error checking shims: {e:#}");
+            true
+        })
+    }
+
     pub fn list_missing_versions(&self) -> Vec<ToolVersion> {
         self.list_current_versions()
             .into_iter()
@@ -242,6 +809,15 @@ impl Toolset {
             .map(|(_, tv)| tv)
             .collect()
     }
+    /// like list_missing_versions but without the tools marked `optional=true`
+    pub fn list_missing_required_versions(&self) -> Vec<ToolVersion> {
+        self.list_missing_versions()
+            .into_iter()
+            .filter(|tv| !tv.is_optional())
+            .collect()
+    }
+    /// installed versions of all tools, or only tools in tool_filter if set
+    /// current versions are taken from list_current_versions so installed_only is honored
     pub fn list_installed_versions(&self) -> Result<Vec<(Arc<dyn Forge>, ToolVersion)>> {
         let current_versions: HashMap<(String, String), (Arc<dyn Forge>, ToolVersion)> = self
             .list_current_versions()
@@ -250,8 +826,13 @@ impl Toolset {
             .collect();
         let versions = forge::list()
             .into_par_iter()
+            .filter(|p| {
+                self.tool_filter
+                    .as_ref()
+                    .map_or(true, |tf| tf.contains(p.fa()))
+            })
             .map(|p| {
-                let versions = p.list_installed_versions()?;
+                let versions = sort_versions(INSTALLED_VERSIONS.get(p.as_ref())?);
                 versions
                     .into_iter()
                     .map(
@@ -259,7 +840,7 @@ impl Toolset {
                             Some((p, tv)) => Ok((p.clone(), tv.clone())),
                             None => {
                                 let tv = ToolRequest::new(p.fa().clone(), &v)?
-                                    .resolve(p.as_ref(), false)
+                                    .resolve(p.as_ref(), false, true)
                                     .unwrap();
                                 Ok((p.clone(), tv))
                             }
@@ -274,6 +855,51 @@ impl Toolset {
 
         Ok(versions)
     }
+    /// uninstalls versions which are not referenced by any tracked config
+    /// symlinked versions and system versions are never pruned
+    pub fn prune(&self, config: &Config, opts: &PruneOptions) -> Result<Vec<ToolVersion>> {
+        let mut to_delete = self
+            .list_installed_versions()?
+            .into_iter()
+            .filter(|(p, tv)| p.symlink_path(tv).is_none())
+            .filter(|(_, tv)| !matches!(tv.request, ToolRequest::System(_)))
+            .map(|(p, tv)| (tv.to_string(), (p, tv)))
+            .collect::<BTreeMap<String, (Arc<dyn Forge>, ToolVersion)>>();
+
+        if let Some(forges) = &opts.forges {
+            to_delete.retain(|_, (_, tv)| forges.contains(&tv.forge));
+        }
+
+        for cf in config.get_tracked_config_files()?.values() {
+            let mut ts = cf.to_toolset()?.clone();
+            if let Err(err) = ts.resolve() {
+                warn!("failed to resolve toolset from {cf}: {err:#}");
+            }
+            for (_, tv) in ts.list_current_versions() {
+                to_delete.remove(&tv.to_string());
+            }
+        }
+
+        let settings = Settings::try_get()?;
+        let mpr = MultiProgressReport::get();
+        let mut pruned = vec![];
+        for (p, tv) in to_delete.into_values() {
+            let mut prefix = tv.style();
+            if opts.dry_run {
+                prefix = format!("{} {} ", prefix, style("[dryrun]").bold());
+            }
+            let pr = mpr.add(&prefix);
+            if opts.dry_run || settings.yes || prompt::confirm(&format!("remove {} ?", &tv))? {
+                p.uninstall_version(&tv, pr.as_ref(), opts.dry_run)?;
+                pr.finish();
+                pruned.push(tv);
+            }
+        }
+        if !opts.dry_run && !pruned.is_empty() {
+            self.reshim()?;
+        }
+        Ok(pruned)
+    }
     pub fn list_plugins(&self) -> Vec<Arc<dyn Forge>> {
         self.list_versions_by_plugin()
             .into_iter()
@@ -301,143 +927,513 @@ impl Toolset {
             .flat_map(|(p, v)| v.iter().map(|v| (p.clone(), v.clone())))
             .collect()
     }
+    /// the current versions of the tools of one backend, e.g.: only cargo tools
+    pub fn list_current_versions_by_type(
+        &self,
+        ft: ForgeType,
+    ) -> Vec<(Arc<dyn Forge>, ToolVersion)> {
+        self.list_current_versions()
+            .into_iter()
+            .filter(|(_, tv)| tv.forge.forge_type == ft)
+            .collect()
+    }
     pub fn list_current_installed_versions(&self) -> Vec<(Arc<dyn Forge>, ToolVersion)> {
         self.list_current_versions()
             .into_iter()
             .filter(|(p, v)| p.is_version_installed(v))
             .collect()
     }
-    pub fn list_outdated_versions(&self) -> Vec<(Arc<dyn Forge>, ToolVersion, String)> {
-        self.list_current_versions()
+    /// true if every requested version is installed, "system" is always considered installed
+    pub fn is_fully_installed(&self) -> bool {
+        self.list_missing_versions().is_empty()
+    }
+    /// true if every requested version is installed and at its latest version
+    /// "system" and symlinked versions are never considered outdated
+    pub fn is_up_to_date(&self) -> bool {
+        self.is_fully_installed()
+            && self
+                .list_outdated_versions()
+                .iter()
+                .all(|o| matches!(o.tv.request, ToolRequest::System(_)))
+    }
+    pub fn list_outdated_versions(&self) -> Vec<OutdatedInfo> {
+        self.update_report()
+            .unwrap_or_default()
             .into_iter()
-            .filter_map(|(t, tv)| {
-                if t.symlink_path(&tv).is_some() {
-                    // do not consider symlinked versions to be outdated
-                    return None;
-                }
-                let latest = match tv.latest_version(t.as_ref()) {
-                    Ok(latest) => latest,
-                    Err(e) => {
-                        warn!("Error getting latest version for {t}: {e:#}");
-                        return None;
-                    }
-                };
-                if !t.is_version_installed(&tv) || tv.version != latest {
-                    Some((t, tv, latest))
-                } else {
+            .filter(|e| e.update_available)
+            .map(OutdatedInfo::from)
+            .collect()
+    }
+    /// the current version of every tool along with the latest version it would be
+    /// updated to. symlinked versions are skipped since they are never considered outdated.
+    pub fn update_report(&self) -> Result<Vec<UpdateEntry>> {
+        let entries = self
+            .list_current_versions()
+            .into_par_iter()
+            .filter(|(t, tv)| t.symlink_path(tv).is_none())
+            .filter_map(|(t, tv)| match tv.latest_version(t.as_ref()) {
+                Ok(latest) => Some(UpdateEntry::new(t, tv, latest)),
+                Err(e) => {
+                    warn!("Error getting latest version for {t}: {e:#}");
                     None
                 }
             })
-            .collect()
+            .collect();
+        Ok(entries)
+    }
+    /// serializes the requested and resolved versions of each tool in this shape:
+    ///
+    /// ```text
+    /// {
+    ///   "node": {
+    ///     "source": { "type": ".mise.toml", "path": "/home/user/.mise.toml" },
+    ///     "requests": ["20", "prefix:18"],
+    ///     "versions": [
+    ///       { "version": "20.1.0", "install_path": "/home/user/...", "installed": true }
+    ///     ]
+    ///   }
+    /// }
+    /// ```
+    pub fn to_json(&self) -> Result<serde_json::Value> {
+        let mut tools = serde_json::Map::new();
+        for (fa, tvl) in &self.versions {
+            let forge = forge::get(fa);
+            let versions = tvl
+                .versions
+                .iter()
+                .map(|tv| {
+                    let mut version = serde_json::Map::new();
+                    version.insert("version".to_string(), tv.version.clone().into());
+                    version.insert(
+                        "install_path".to_string(),
+                        serde_json::to_value(tv.install_path())?,
+                    );
+                    version.insert(
+                        "installed".to_string(),
+                        forge.is_version_installed(tv).into(),
+                    );
+                    Ok(serde_json::Value::Object(version))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let requests = tvl.requests.iter().map(|tr| tr.version()).collect_vec();
+            let mut tool = serde_json::Map::new();
+            tool.insert(
+                "source".to_string(),
+                serde_json::to_value(tvl.source.as_json())?,
+            );
+            tool.insert("requests".to_string(), requests.into());
+            tool.insert("versions".to_string(), versions.into());
+            tools.insert(fa.id.clone(), serde_json::Value::Object(tool));
+        }
+        Ok(serde_json::Value::Object(tools))
+    }
+    pub fn full_env(&self, config: &Config) -> Result<BTreeMap<String, String>> {
+        let mut env = env::PRISTINE_ENV
+            .clone()
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        env.extend(self.env_with_path_cached(config)?);
+        Ok(env)
+    }
+    /// like env_with_path but the tools' part of it is read from tool_env_cached
+    fn env_with_path_cached(&self, config: &Config) -> Result<BTreeMap<String, String>> {
+        let (tool_env, paths) = self.tool_env_cached(config)?;
+        self.env_with_path_from(config, tool_env, paths)
+    }
+    /// the tools' exec-env and bin paths are cached on disk for full_env since shell prompts run
+    /// it often, the config's env is never written to the cache since it often holds secrets
+    /// the cache is keyed on the installed versions with their install and plugin dirs, and the
+    /// settings. the env isn't part of the key since vars like PWD change with every cd. it goes
+    /// stale when one of those dirs changes, installs clear it with invalidate_env_cache
+    fn tool_env_cached(&self, config: &Config) -> Result<(ToolEnv, Vec<PathBuf>)> {
+        let settings = Settings::try_get()?;
+        let versions = self
+            .list_current_installed_versions()
+            .into_iter()
+            .filter(|(_, tv)| !matches!(tv.request, ToolRequest::System(_)))
+            .collect_vec();
+        let tool_dirs = versions
+            .iter()
+            .flat_map(|(p, tv)| [tv.install_path(), dirs::PLUGINS.join(p.name())])
+            .filter(|dir| dir.exists())
+            .unique()
+            .collect_vec();
+        let key = hash_to_str(&(
+            versions
+                .iter()
+                .map(|(_, tv)| (tv.to_string(), tv.request.options()))
+                .collect_vec(),
+            &tool_dirs,
+            serde_json::to_string(&*settings)?,
+        ));
+        let cache = tool_dirs.iter().fold(
+            CacheManager::new(env_cache_dir().join(format!("{key}.msgpack.z")))
+                .with_fresh_file(dirs::DATA.to_path_buf()),
+            |cache, dir| cache.with_fresh_file(dir.clone()),
+        );
+        // stored by tool id since ForgeArg isn't serializable
+        let (tool_env, paths): &(BTreeMap<String, (String, String)>, Vec<PathBuf>) = cache
+            .get_or_try_init(|| {
+                let tool_env = self
+                    .tool_env(config, &HashSet::new())?
+                    .into_iter()
+                    .map(|(k, (v, fa))| (k, (v, fa.id)))
+                    .collect();
+                Ok((tool_env, self.list_paths()))
+            })?;
+        let tool_env = tool_env
+            .iter()
+            .map(|(k, (v, id))| (k.clone(), (v.clone(), ForgeArg::from(id.as_str()))))
+            .collect();
+        Ok((tool_env, paths.clone()))
     }
-    pub fn full_env(&self) -> Result<BTreeMap<String, String>> {
+    /// like full_env but without the exec-env and bin paths of the excluded tools
+    pub fn env_excluding(
+        &self,
+        config: &Config,
+        exclude: &HashSet<ForgeArg>,
+    ) -> Result<BTreeMap<String, String>> {
         let mut env = env::PRISTINE_ENV
             .clone()
             .into_iter()
             .collect::<BTreeMap<_, _>>();
-        env.extend(self.env_with_path(&Config::get())?);
+        env.extend(self.env_with_path_excluding(config, exclude)?);
         Ok(env)
     }
+    /// env vars mise would add, change, or remove relative to the pristine env
+    /// PATH is compared by entry so only new entries end up in `diff.path`
+    pub fn env_diff(&self, config: &Config) -> Result<EnvDiff> {
+        let mut env = self.full_env(config)?;
+        let path = env.remove("PATH").unwrap_or_default();
+        let mut diff = EnvDiff::new(&env::PRISTINE_ENV, env.clone());
+        for (k, v) in env::PRISTINE_ENV.iter() {
+            if k != "PATH" && !env.contains_key(k) {
+                diff.old.insert(k.clone(), v.clone());
+            }
+        }
+        diff.path = split_paths(&path)
+            .filter(|p| !env::PATH.contains(p))
+            .unique()
+            .collect();
+        Ok(diff)
+    }
     pub fn env_with_path(&self, config: &Config) -> Result<BTreeMap<String, String>> {
-        let mut path_env = PathEnv::from_iter(env::PATH.clone());
+        self.env_with_path_excluding(config, &HashSet::new())
+    }
+    fn env_with_path_excluding(
+        &self,
+        config: &Config,
+        exclude: &HashSet<ForgeArg>,
+    ) -> Result<BTreeMap<String, String>> {
+        let tool_env = self.tool_env(config, exclude)?;
+        self.env_with_path_from(config, tool_env, self.list_paths_excluding(exclude))
+    }
+    /// adds the config's env and PATH to the tools' env and bin paths
+    fn env_with_path_from(
+        &self,
+        config: &Config,
+        tool_env: ToolEnv,
+        paths: Vec<PathBuf>,
+    ) -> Result<BTreeMap<String, String>> {
+        let settings = Settings::try_get()?;
+        let mut path_env = base_path_env(&settings, &env::PATH);
         for p in config.path_dirs()?.clone() {
             path_env.add(p);
         }
-        let mut env = self.env(config)?;
+        let mut env: BTreeMap<String, String> = with_config_env(config, tool_env)?
+            .into_iter()
+            .map(|(k, (v, _))| (k, v))
+            .collect();
+        let remove_paths = env.remove("MISE_REMOVE_PATH");
         if let Some(path) = env.get("PATH") {
-            path_env.add(PathBuf::from(path));
+            for p in split_paths(path) {
+                path_env.add(p);
+            }
         }
-        for p in self.list_paths() {
+        for p in paths {
             path_env.add(p);
         }
+        // removals apply after all additions so a tool can hide a path added by another tool
+        if let Some(remove_paths) = remove_paths {
+            for p in split_paths(&remove_paths) {
+                path_env.remove(&p);
+            }
+        }
         env.insert("PATH".to_string(), path_env.to_string());
         Ok(env)
     }
+    /// env vars set by the exec-env of the current tools and the config's env
+    /// if several tools set the same var the one which comes first in the toolset wins, i.e.: the
+    /// tool from the config with the highest precedence or the earlier line of a .tool-versions
+    ///
+    /// precedence, highest first:
+    /// 1. the config's env, except vars in the env_tool_override setting which a tool sets
+    /// 2. env_* tool options
+    /// 3. the exec-env of the tools
+    /// 4. the config's env vars in env_tool_override
     pub fn env(&self, config: &Config) -> Result<BTreeMap<String, String>> {
+        let env = self
+            .env_with_sources(config)?
+            .into_iter()
+            .map(|(k, (v, _))| (k, v))
+            .collect();
+        Ok(env)
+    }
+    /// like env but also returns what set each var
+    pub fn env_with_sources(
+        &self,
+        config: &Config,
+    ) -> Result<BTreeMap<String, (String, EnvSource)>> {
+        self.env_with_sources_excluding(config, &HashSet::new())
+    }
+    fn env_with_sources_excluding(
+        &self,
+        config: &Config,
+        exclude: &HashSet<ForgeArg>,
+    ) -> Result<BTreeMap<String, (String, EnvSource)>> {
+        with_config_env(config, self.tool_env(config, exclude)?)
+    }
+    /// the merged exec-env and env_* options of the current tools without the config's env
+    fn tool_env(&self, config: &Config, exclude: &HashSet<ForgeArg>) -> Result<ToolEnv> {
         let entries = self
             .list_current_installed_versions()
             .into_par_iter()
+            .filter(|(p, _)| !exclude.contains(p.fa()))
             .filter(|(_, tv)| !matches!(tv.request, ToolRequest::System(_)))
-            .flat_map(|(p, tv)| match p.exec_env(config, self, &tv) {
-                Ok(env) => env.into_iter().collect(),
-                Err(e) => {
-                    warn!("Error running exec-env: {:#}", e);
-                    Vec::new()
+            .map(|(p, tv)| {
+                // env_* tool options come first so they override the tool's own exec-env
+                let mut env = tool_options_env(p.as_ref(), &tv)?;
+                match p.exec_env(config, self, &tv) {
+                    Ok(exec_env) => env.extend(exec_env),
+                    Err(e) => warn!("Error running exec-env: {:#}", e),
                 }
+                Ok(env
+                    .into_iter()
+                    .map(|(k, v)| (k, v, p.fa().clone()))
+                    .collect_vec())
             })
-            .collect::<Vec<(String, String)>>();
-        let add_paths = entries
-            .iter()
-            .filter(|(k, _)| k == "MISE_ADD_PATH" || k == "RTX_ADD_PATH")
-            .map(|(_, v)| v)
-            .join(":");
-        let mut entries: BTreeMap<String, String> = entries
+            .collect::<Result<Vec<_>>>()?
             .into_iter()
-            .filter(|(k, _)| k != "RTX_ADD_PATH")
-            .filter(|(k, _)| k != "MISE_ADD_PATH")
-            .filter(|(k, _)| !k.starts_with("RTX_TOOL_OPTS__"))
-            .filter(|(k, _)| !k.starts_with("MISE_TOOL_OPTS__"))
-            .rev()
-            .collect();
-        if !add_paths.is_empty() {
-            entries.insert("PATH".to_string(), add_paths);
-        }
-        entries.extend(config.env()?.clone());
-        Ok(entries)
+            .flatten()
+            .collect::<Vec<(String, String, ForgeArg)>>();
+        Ok(merge_exec_env(entries))
     }
     pub fn list_paths(&self) -> Vec<PathBuf> {
+        self.list_paths_excluding(&HashSet::new())
+    }
+    fn list_paths_excluding(&self, exclude: &HashSet<ForgeArg>) -> Vec<PathBuf> {
         self.list_current_installed_versions()
             .into_par_iter()
+            .filter(|(p, _)| !exclude.contains(p.fa()))
             .filter(|(_, tv)| !matches!(tv.request, ToolRequest::System(_)))
-            .flat_map(|(p, tv)| {
-                p.list_bin_paths(&tv).unwrap_or_else(|e| {
-                    warn!("Error listing bin paths for {tv}: {e:#}");
-                    Vec::new()
-                })
-            })
+            .flat_map(|(p, tv)| list_bin_paths(p.as_ref(), &tv))
             .collect()
     }
-    pub fn which(&self, bin_name: &str) -> Option<(Arc<dyn Forge>, ToolVersion)> {
-        self.list_current_installed_versions()
-            .into_par_iter()
-            .find_first(|(p, tv)| {
-                if let Ok(x) = p.which(tv, bin_name) {
-                    x.is_some()
-                } else {
-                    false
-                }
-            })
+    /// the PATH exec-env scripts run with: the toolset's bin paths in toolset order, then the
+    /// pristine PATH. It is derived only from the toolset and the pristine env so a tool's
+    /// exec-env doesn't depend on which other tools' exec-env ran before it.
+    pub fn exec_env_path(&self) -> Result<OsString> {
+        let settings = Settings::try_get()?;
+        let mut path_env = base_path_env(&settings, &env::PATH);
+        for p in self.list_paths() {
+            path_env.add(p);
+        }
+        Ok(path_env.join())
     }
-    pub fn install_missing_bin(&mut self, bin_name: &str) -> Result<Option<Vec<ToolVersion>>> {
-        let config = Config::try_get()?;
-        let plugins = self
-            .list_installed_versions()?
+    /// problems with PATH such as the shims dir missing from it or a tool's bins being shadowed
+    pub fn doctor_path(&self) -> Vec<PathIssue> {
+        let bin_paths = self
+            .list_current_installed_versions()
             .into_iter()
-            .filter(|(p, tv)| {
-                if let Ok(x) = p.which(tv, bin_name) {
-                    x.is_some()
-                } else {
-                    false
-                }
+            .filter(|(_, tv)| !matches!(tv.request, ToolRequest::System(_)))
+            .flat_map(|(p, tv)| {
+                list_bin_paths(p.as_ref(), &tv)
+                    .into_iter()
+                    .map(move |bin_path| (p.fa().clone(), bin_path))
             })
             .collect_vec();
-        for (plugin, _) in plugins {
-            let versions = self
-                .list_missing_versions()
+        PathIssue::find(&env::PATH, &dirs::SHIMS, &bin_paths)
+    }
+    /// bin paths of a single tool, the active version's paths come first
+    pub fn list_bin_paths_for(&self, fa: &ForgeArg) -> Vec<PathBuf> {
+        let versions = self
+            .list_current_installed_versions()
+            .into_iter()
+            .filter(|(p, tv)| p.fa() == fa && !matches!(tv.request, ToolRequest::System(_)))
+            .collect_vec();
+        if versions.is_empty() {
+            warn!("{fa} is not installed");
+        }
+        versions
+            .into_iter()
+            .flat_map(|(p, tv)| list_bin_paths(p.as_ref(), &tv))
+            .collect()
+    }
+    /// the installed version which provides bin_name, cached per bin name, see WhichCache
+    pub fn which(&self, bin_name: &str) -> Option<(Arc<dyn Forge>, ToolVersion)> {
+        if let Some(found) = self.which_cache.get(bin_name) {
+            return found.map(|(fa, tv)| (forge::get(&fa), tv));
+        }
+        let found = self.which_uncached(bin_name);
+        let cached = found.as_ref().map(|(p, tv)| (p.fa().clone(), tv.clone()));
+        self.which_cache.insert(bin_name, cached);
+        found
+    }
+    fn which_uncached(&self, bin_name: &str) -> Option<(Arc<dyn Forge>, ToolVersion)> {
+        let (p, tv) = self.which_all(bin_name).into_iter().next()?;
+        match self.latest_installed(p.fa()) {
+            Some(latest) if matches!(p.which(&latest, bin_name), Ok(Some(_))) => Some((p, latest)),
+            _ => Some((p, tv)),
+        }
+    }
+    /// the installed version of a tool which should be used, this is the first installed version
+    /// in request order so a pinned request wins over "latest"
+    /// if the tool is not in the toolset, the highest installed version is used
+    pub fn latest_installed(&self, fa: &ForgeArg) -> Option<ToolVersion> {
+        let p = forge::get(fa);
+        if let Some(tvl) = self.versions.get(fa) {
+            return tvl
+                .versions
+                .iter()
+                .find(|tv| p.is_version_installed(tv))
+                .cloned();
+        }
+        let v = INSTALLED_VERSIONS.get(p.as_ref()).ok()?.pop()?;
+        ToolRequest::new(fa.clone(), &v)
+            .and_then(|tr| tr.resolve(p.as_ref(), false, true))
+            .ok()
+    }
+    /// all installed versions which provide bin_name, in the order their bin paths are in the
+    /// PATH env_with_path builds (see path_order) so the first one is what will actually run
+    pub fn which_all(&self, bin_name: &str) -> Vec<(Arc<dyn Forge>, ToolVersion)> {
+        let found = self
+            .list_current_installed_versions()
+            .into_par_iter()
+            .filter_map(|(p, tv)| match p.which(&tv, bin_name) {
+                Ok(Some(bin)) => Some(((p, tv), bin)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if found.len() < 2 {
+            return found.into_iter().map(|(t, _)| t).collect();
+        }
+        sort_by_path_order(found, &self.path_order())
+    }
+    /// the dirs of PATH in the order env_with_path puts them, config path_dirs and PATH entries
+    /// from the env or a tool's exec-env come before the tools' own bin paths
+    /// falls back to the bin paths in toolset order if the env can't be built
+    fn path_order(&self) -> Vec<PathBuf> {
+        let env = Config::try_get().and_then(|config| self.env_with_path_cached(&config));
+        match env {
+            Ok(env) => split_paths(&env["PATH"]).collect(),
+            Err(err) => {
+                debug!("unable to build PATH, using the toolset order: {err:#}");
+                self.list_paths()
+            }
+        }
+    }
+    pub fn install_missing_bin(&mut self, bin_name: &str) -> Result<Option<Vec<ToolVersion>>> {
+        let config = Config::try_get()?;
+        let missing = self.missing_versions_with_bin(bin_name)?;
+        if let Some(fa) = missing.first().map(|tv| tv.forge.clone()) {
+            let versions = missing
                 .into_iter()
-                .filter(|tv| &tv.forge == plugin.fa())
+                .filter(|tv| tv.forge == fa)
                 .map(|tv| tv.request)
                 .collect_vec();
-            if !versions.is_empty() {
-                let mpr = MultiProgressReport::get();
-                let versions =
-                    self.install_versions(&config, versions.clone(), &mpr, &InstallOptions::new())?;
-                return Ok(Some(versions));
-            }
+            let mpr = MultiProgressReport::get();
+            let versions =
+                self.install_versions(&config, versions, &mpr, &InstallOptions::new())?;
+            return Ok(Some(versions));
         }
         Ok(None)
     }
 
+    /// like `which` but if no installed version provides bin_name, reports the requested versions
+    /// which are not installed yet but would likely provide it
+    pub fn which_with_install_hint(&self, bin_name: &str) -> WhichResult {
+        if let Some((p, tv)) = self.which(bin_name) {
+            return WhichResult::Found(p, tv);
+        }
+        match self.missing_versions_with_bin(bin_name) {
+            Ok(missing) if !missing.is_empty() => WhichResult::NotInstalled(missing),
+            Ok(_) => WhichResult::NotFound,
+            Err(e) => {
+                warn!("Error finding tools with {bin_name}: {e:#}");
+                WhichResult::NotFound
+            }
+        }
+    }
+
+    /// the bin in the PATH mise was started with if a tool which provides it is requested as
+    /// "system", e.g.: `python = "system"` for python. a tool provides a bin with its own name or
+    /// one of its installed versions' bins
+    pub fn which_system(&self, bin_name: &str) -> Option<(ToolVersion, PathBuf)> {
+        let system = self
+            .list_current_versions()
+            .into_iter()
+            .filter(|(_, tv)| matches!(tv.request, ToolRequest::System(_)))
+            .collect_vec();
+        if system.is_empty() {
+            return None;
+        }
+        let installed = self.list_installed_versions().unwrap_or_default();
+        let provides = |p: &Arc<dyn Forge>| {
+            p.name() == bin_name
+                || installed.iter().any(|(ip, tv)| {
+                    ip.id() == p.id() && matches!(ip.which(tv, bin_name), Ok(Some(_)))
+                })
+        };
+        let (_, tv) = system.into_iter().find(|(p, _)| provides(p))?;
+        let paths = env::PATH
+            .iter()
+            .filter(|p| p.as_path() != *dirs::SHIMS)
+            .cloned()
+            .collect_vec();
+        let bin = find_bin(&paths, bin_name)?;
+        Some((tv, bin))
+    }
+
+    /// any installed version which provides bin_name even if it isn't a current version,
+    /// e.g.: node@18 when node@20 is requested but not installed. the newest version is preferred
+    pub fn which_any_installed(&self, bin_name: &str) -> Option<(Arc<dyn Forge>, ToolVersion)> {
+        let installed = match self.list_installed_versions() {
+            Ok(installed) => installed,
+            Err(e) => {
+                warn!("Error listing installed versions: {e:#}");
+                return None;
+            }
+        };
+        installed
+            .into_iter()
+            .rev()
+            .sorted_by(|(a, _), (b, _)| a.id().cmp(b.id()))
+            .find(|(p, tv)| matches!(p.which(tv, bin_name), Ok(Some(_))))
+    }
+
+    /// missing versions of tools which have another version installed that provides bin_name
+    /// sorted by forge id so the first tool is picked deterministically
+    fn missing_versions_with_bin(&self, bin_name: &str) -> Result<Vec<ToolVersion>> {
+        let forges = self
+            .list_installed_versions()?
+            .into_par_iter()
+            .filter(|(p, tv)| matches!(p.which(tv, bin_name), Ok(Some(_))))
+            .map(|(p, _)| p.fa().clone())
+            .collect::<Vec<_>>()
+            .into_iter()
+            // tools which typically provide the bin don't need a version installed already
+            .chain(forge::forge_for_bin(bin_name))
+            .unique()
+            .sorted_by(|a, b| a.id.cmp(&b.id))
+            .collect_vec();
+        let missing = self.list_missing_versions();
+        Ok(forges
+            .iter()
+            .flat_map(|fa| missing.iter().filter(move |tv| &tv.forge == fa))
+            .cloned()
+            .collect())
+    }
+
     pub fn list_rtvs_with_bin(&self, bin_name: &str) -> Result<Vec<ToolVersion>> {
         Ok(self
             .list_installed_versions()?
@@ -457,18 +1453,7 @@ impl Toolset {
     // only displays for tools which have at least one version already installed
     pub fn notify_if_versions_missing(&self) {
         let settings = Settings::get();
-        let missing = self
-            .list_missing_versions()
-            .into_iter()
-            .filter(|tv| match settings.status.missing_tools {
-                SettingsStatusMissingTools::Never => false,
-                SettingsStatusMissingTools::Always => true,
-                SettingsStatusMissingTools::IfOtherVersionsInstalled => tv
-                    .get_forge()
-                    .list_installed_versions()
-                    .is_ok_and(|f| !f.is_empty()),
-            })
-            .collect_vec();
+        let missing = missing_to_notify(self.list_missing_required_versions(), &settings.status);
         if missing.is_empty() || *env::__MISE_SHIM {
             return;
         }
@@ -477,15 +1462,37 @@ impl Toolset {
             .map(|tv| tv.style())
             .collect::<Vec<_>>()
             .join(" ");
-        warn!(
-            "missing: {}",
-            truncate_str(&versions, *TERM_WIDTH - 14, "…"),
-        );
+        warn!("missing: {}", truncate_missing(&versions, *TERM_WIDTH));
     }
 
     fn is_disabled(&self, fa: &ForgeArg) -> bool {
-        self.tool_filter.as_ref().is_some_and(|tf| !tf.contains(fa))
-            || self.disable_tools.contains(fa)
+        self.disabled_by(fa).is_some()
+    }
+
+    /// the setting which causes requests for a tool to be ignored, if any
+    pub fn disabled_by(&self, fa: &ForgeArg) -> Option<&'static str> {
+        if self.disable_tools.contains(fa) {
+            Some("the disable_tools setting")
+        } else if self.tool_filter.as_ref().is_some_and(|tf| !tf.contains(fa)) {
+            Some("the tool filter")
+        } else {
+            None
+        }
+    }
+}
+
+/// which_cache is left out since it only depends on the other fields
+impl Debug for Toolset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Toolset")
+            .field("versions", &self.versions)
+            .field("source", &self.source)
+            .field("disable_tools", &self.disable_tools)
+            .field("tool_filter", &self.tool_filter)
+            .field("installed_only", &self.installed_only)
+            .field("skipped_sources", &self.skipped_sources)
+            .field("lockfile", &self.lockfile)
+            .finish()
     }
 }
 
@@ -507,7 +1514,10 @@ impl From<ToolRequestSet> for Toolset {
             ..Default::default()
         };
         for (fa, versions) in trs.tools {
-            let mut tvl = ToolVersionList::new(fa.clone(), ts.source.as_ref().unwrap().clone());
+            // keep the per-tool source so e.g.: env var and argument tools are not
+            // reported as coming from a config file
+            let source = trs.sources.remove(&fa).or_else(|| ts.source.clone());
+            let mut tvl = ToolVersionList::new(fa.clone(), source.unwrap());
             for tr in versions {
                 tvl.requests.push(tr);
             }
@@ -517,6 +1527,420 @@ impl From<ToolRequestSet> for Toolset {
     }
 }
 
+/// the install stops itself at ctx.deadline and has already cleaned up after itself by the time
+/// it returns Error::TimedOut
+fn install_version(t: &dyn Forge, ctx: &InstallContext) -> Result<()> {
+    let tv = ctx.tv.clone();
+    let start = Instant::now();
+    t.install_version(ctx).map_err(|err| {
+        let timed_out = err
+            .chain()
+            .any(|e| matches!(e.downcast_ref::<Error>(), Some(Error::TimedOut(_))));
+        match timed_out {
+            true => err.wrap_err(format!(
+                "timed out installing {tv} after {:.2?}",
+                start.elapsed()
+            )),
+            false => err,
+        }
+    })
+}
+
+/// turns a panic while installing tr into an error so the other tools are still installed and
+/// reshimmed, the failure is then reported with Error::InstallFailed
+fn catch_install_panic<T>(tr: &ToolRequest, f: impl FnOnce() -> Result<T>) -> Result<Result<T>> {
+    panic::catch_unwind(panic::AssertUnwindSafe(f)).map_err(|e| {
+        let msg = e
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| e.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        eyre!("panicked while installing {tr}: {msg}")
+    })
+}
+
+/// env var -> (value, tool which set it), see Toolset::tool_env
+type ToolEnv = BTreeMap<String, (String, ForgeArg)>;
+
+/// the tools' env with the config's env merged in, see Toolset::env
+fn with_config_env(
+    config: &Config,
+    tool_env: ToolEnv,
+) -> Result<BTreeMap<String, (String, EnvSource)>> {
+    let mut entries: BTreeMap<String, (String, EnvSource)> = tool_env
+        .into_iter()
+        .map(|(k, (v, fa))| (k, (v, EnvSource::Tool(fa))))
+        .collect();
+    let settings = Settings::try_get()?;
+    merge_config_env(&mut entries, &config.env()?, &settings.env_tool_override);
+    Ok(entries)
+}
+
+fn env_cache_dir() -> PathBuf {
+    dirs::CACHE.join("env")
+}
+
+/// clears the cached env of full_env, e.g.: after installs since a reinstalled tool can have a
+/// different exec-env or bin paths
+pub fn invalidate_env_cache() -> Result<()> {
+    file::remove_all(env_cache_dir())
+}
+
+/// the PATH which config and tool paths are added to, it is not inherited with hermetic_path
+fn base_path_env(settings: &Settings, inherited: &[PathBuf]) -> PathEnv {
+    match settings.hermetic_path {
+        true => PathEnv::hermetic(settings.hermetic_path_base.clone()),
+        false => PathEnv::from_iter(inherited.to_vec()),
+    }
+}
+
+/// fits the missing versions on one line after the "mise WARN  missing: " prefix
+/// truncate_str measures display width so styles and wide characters are accounted for, it
+/// underflows if the width is less than the tail's so at least "…" is always shown
+fn truncate_missing(versions: &str, term_width: usize) -> String {
+    let width = term_width.saturating_sub(14).max(1);
+    truncate_str(versions, width, "…").to_string()
+}
+
+fn list_bin_paths(p: &dyn Forge, tv: &ToolVersion) -> Vec<PathBuf> {
+    tv.list_bin_paths(p).unwrap_or_else(|e| {
+        warn!("Error listing bin paths for {tv}: {e:#}");
+        Vec::new()
+    })
+}
+
+fn list_remote_versions(t: &dyn Forge, refresh: bool, offline: bool) -> Result<Vec<String>> {
+    if offline {
+        return cached_remote_versions(t);
+    }
+    let path = t.fa().cache_path.join("remote_versions_sorted.msgpack.z");
+    let cache = CacheManager::new(path)
+        .with_fresh_duration(*env::MISE_FETCH_REMOTE_VERSIONS_CACHE)
+        .with_no_cache(refresh);
+    let versions = cache.get_or_try_init(|| {
+        let mut versions = sort_versions(t.list_remote_versions()?);
+        versions.reverse();
+        Ok(versions)
+    })?;
+    Ok(versions.clone())
+}
+
+/// the remote versions from the cache the forges keep themselves, e.g.:
+/// CorePlugin::remote_version_cache, which any resolve or install that listed them has written
+/// offline it is used regardless of its age
+fn cached_remote_versions(t: &dyn Forge) -> Result<Vec<String>> {
+    let path = t.fa().cache_path.join("remote_versions.msgpack.z");
+    ensure!(
+        path.exists(),
+        "required the remote versions of {t} but offline, run `mise ls-remote {t}` first"
+    );
+    let versions = CacheManager::<Vec<String>>::new(path)
+        .get_or_try_init_offline(true, || unreachable!("nothing is fetched offline"))?;
+    let mut versions = sort_versions(versions.clone());
+    versions.reverse();
+    Ok(versions)
+}
+
+/// the missing versions which aren't ignored by status.missing_tools_ignore, then filtered
+/// by status.missing_tools
+fn missing_to_notify(missing: Vec<ToolVersion>, status: &SettingsStatus) -> Vec<ToolVersion> {
+    let mut ignore = GlobSetBuilder::new();
+    for pattern in &status.missing_tools_ignore {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                ignore.add(glob);
+            }
+            Err(err) => warn!("invalid status.missing_tools_ignore pattern {pattern}: {err}"),
+        }
+    }
+    let ignore = ignore.build().unwrap_or_else(|_| GlobSet::empty());
+    missing
+        .into_iter()
+        .filter(|tv| !ignore.is_match(&tv.forge.id))
+        .filter(|tv| match status.missing_tools {
+            SettingsStatusMissingTools::Never => false,
+            SettingsStatusMissingTools::Always => true,
+            SettingsStatusMissingTools::IfOtherVersionsInstalled => tv
+                .get_forge()
+                .list_installed_versions()
+                .is_ok_and(|f| !f.is_empty()),
+        })
+        .collect()
+}
+
+fn resolve_for_install(
+    t: &dyn Forge,
+    tr: ToolRequest,
+    opts: &InstallOptions,
+) -> Result<ToolVersion> {
+    if !opts.offline {
+        return tr.resolve(t, opts.latest_versions, opts.prefer_stable);
+    }
+    let mut remote_versions = list_remote_versions(t, false, true)?;
+    remote_versions.reverse();
+    let t = OfflineForge {
+        forge: t,
+        remote_versions,
+    };
+    tr.resolve(&t, opts.latest_versions, opts.prefer_stable)
+}
+
+/// with latest_versions each request would fetch the remote versions of its forge again inside
+/// the workers, this resolves them all up front with a single (cached) fetch per forge
+/// requests which fail to resolve here are left to the workers so their errors are reported
+/// install_arg_versions sets latest_versions for reinstall_if_outdated so it's covered as well
+fn resolve_latest_for_install(
+    queue: &[(AForge, Vec<ToolRequest>)],
+    opts: &InstallOptions,
+) -> HashMap<ToolRequest, ToolVersion> {
+    let mut resolved = HashMap::new();
+    if !opts.latest_versions || opts.offline {
+        return resolved;
+    }
+    for (t, trs) in queue {
+        let needs_remote = trs.iter().any(|tr| {
+            !matches!(
+                tr,
+                ToolRequest::Ref { .. } | ToolRequest::Path(..) | ToolRequest::System(..)
+            )
+        });
+        if !needs_remote {
+            continue;
+        }
+        let mut remote_versions = match list_remote_versions(t.as_ref(), false, false) {
+            Ok(remote_versions) => remote_versions,
+            Err(err) => {
+                debug!("failed to list remote versions of {t}: {err:#}");
+                continue;
+            }
+        };
+        remote_versions.reverse();
+        let ot = OfflineForge {
+            forge: t.as_ref(),
+            remote_versions,
+        };
+        for tr in trs
+            .iter()
+            .filter(|tr| !resolves_through_latest(t.as_ref(), tr))
+        {
+            if let Ok(tv) = tr.resolve(&ot, true, opts.prefer_stable) {
+                resolved.insert(tr.clone(), tv);
+            }
+        }
+    }
+    resolved
+}
+
+/// "latest" is resolved with Forge::latest_stable_version which forges can override to not just
+/// pick the highest remote version, e.g.: npm dist-tags or the latest-stable script of plugins.
+/// OfflineForge only has the remote versions so these requests are resolved by the real forge
+fn resolves_through_latest(t: &dyn Forge, tr: &ToolRequest) -> bool {
+    let v = match tr {
+        ToolRequest::Version { version, .. } => version,
+        ToolRequest::Sub { orig_version, .. } => orig_version,
+        _ => return false,
+    };
+    match Config::get().resolve_alias(t, v) {
+        Ok(v) => v == "latest" || v.ends_with(":latest"),
+        Err(_) => true,
+    }
+}
+
+/// resolves versions against an already fetched list of remote versions of a forge
+/// see InstallOptions::offline and resolve_latest_for_install
+#[derive(Debug)]
+struct OfflineForge<'a> {
+    forge: &'a dyn Forge,
+    /// oldest first like Forge::list_remote_versions
+    remote_versions: Vec<String>,
+}
+
+impl Forge for OfflineForge<'_> {
+    fn get_type(&self) -> ForgeType {
+        self.forge.get_type()
+    }
+    fn fa(&self) -> &ForgeArg {
+        self.forge.fa()
+    }
+    fn list_remote_versions(&self) -> Result<Vec<String>> {
+        Ok(self.remote_versions.clone())
+    }
+    fn _list_remote_versions(&self) -> Result<Vec<String>> {
+        Ok(self.remote_versions.clone())
+    }
+    fn list_installed_versions(&self) -> Result<Vec<String>> {
+        self.forge.list_installed_versions()
+    }
+    fn is_installed(&self) -> bool {
+        self.forge.is_installed()
+    }
+    fn get_aliases(&self) -> Result<BTreeMap<String, String>> {
+        self.forge.get_aliases()
+    }
+    fn install_version_impl(&self, _ctx: &InstallContext) -> Result<()> {
+        bail!("{} is only used to resolve versions", self.forge)
+    }
+}
+
+/// true if a newer version than the installed one is available
+/// symlinked versions are never considered outdated
+fn is_outdated(t: &dyn Forge, tv: &ToolVersion) -> bool {
+    if t.symlink_path(tv).is_some() {
+        return false;
+    }
+    match tv.latest_version(t) {
+        Ok(latest) => tv.version != latest,
+        Err(e) => {
+            warn!("Error getting latest version for {t}: {e:#}");
+            false
+        }
+    }
+}
+
+/// takes the next queued job whose forge type is below its forge_jobs limit
+/// and marks it as installing
+fn pick_job(
+    queue: &mut Vec<(AForge, Vec<ToolRequest>)>,
+    installing: &mut HashMap<String, ForgeType>,
+    forge_jobs: &BTreeMap<ForgeType, usize>,
+) -> Option<(AForge, Vec<ToolRequest>)> {
+    let idx = queue.iter().rposition(|(t, _)| {
+        let forge_type = t.fa().forge_type;
+        forge_jobs.get(&forge_type).map_or(true, |&max| {
+            installing.values().filter(|ft| **ft == forge_type).count() < max
+        })
+    })?;
+    let (t, versions) = queue.remove(idx);
+    installing.insert(t.id().into(), t.fa().forge_type);
+    Some((t, versions))
+}
+
+/// sleeps with exponential backoff (1s, 2s, 4s, ...) while letting ctrl-c exit
+fn backoff(attempt: usize, cancel: &AtomicBool) -> Result<()> {
+    let until = Instant::now() + Duration::from_secs(1 << (attempt - 1).min(6));
+    while Instant::now() < until {
+        ensure!(!cancel.load(Ordering::Relaxed), "install cancelled");
+        sleep(Duration::from_millis(100));
+    }
+    Ok(())
+}
+
+/// sorts bins by the position of their directory in paths, bins outside of paths go last
+fn sort_by_path_order<T>(found: Vec<(T, PathBuf)>, paths: &[PathBuf]) -> Vec<T> {
+    found
+        .into_iter()
+        .sorted_by_cached_key(|(_, bin)| {
+            paths
+                .iter()
+                .position(|p| bin.parent() == Some(p.as_path()))
+                .unwrap_or(usize::MAX)
+        })
+        .map(|(t, _)| t)
+        .collect()
+}
+
+/// sorts versions semver-aware so "1.10.0" comes after "1.9.0", versions which can't be parsed
+/// are sorted lexically before the others and equal versions keep their order
+fn sort_versions(versions: Vec<String>) -> Vec<String> {
+    versions
+        .into_iter()
+        .sorted_by_cached_key(|v| match Versioning::new(v) {
+            Some(version) => (Some(version), None),
+            None => (None, Some(v.clone())),
+        })
+        .collect()
+}
+
+/// merges the (key, value, tool id) exec-env entries of all tools which are in toolset order
+fn merge_exec_env(
+    entries: Vec<(String, String, ForgeArg)>,
+) -> BTreeMap<String, (String, ForgeArg)> {
+    for conflict in env_conflicts(&entries) {
+        warn!("{conflict}");
+    }
+    // the combined paths are attributed to the first tool which added to them
+    let join_paths = |keys: [&str; 2]| {
+        let paths = entries
+            .iter()
+            .filter(|(k, _, _)| keys.contains(&k.as_str()))
+            .collect_vec();
+        let fa = paths.first().map(|(_, _, fa)| fa.clone())?;
+        // several tools can add the same dir, only its first occurrence is kept
+        let joined = paths
+            .iter()
+            .flat_map(|(_, v, _)| v.split(':'))
+            .filter(|p| !p.is_empty())
+            .unique()
+            .join(":");
+        Some((joined, fa))
+    };
+    let add_paths = join_paths(["MISE_ADD_PATH", "RTX_ADD_PATH"]);
+    let remove_paths = join_paths(["MISE_REMOVE_PATH", "RTX_REMOVE_PATH"]);
+    let mut env = BTreeMap::new();
+    for (k, v, fa) in entries
+        .into_iter()
+        .filter(|(k, _, _)| k != "RTX_ADD_PATH")
+        .filter(|(k, _, _)| k != "MISE_ADD_PATH")
+        .filter(|(k, _, _)| k != "RTX_REMOVE_PATH")
+        .filter(|(k, _, _)| k != "MISE_REMOVE_PATH")
+        .filter(|(k, _, _)| !k.starts_with("RTX_TOOL_OPTS__"))
+        .filter(|(k, _, _)| !k.starts_with("MISE_TOOL_OPTS__"))
+    {
+        // entries are in toolset order so the first tool to set a var wins
+        env.entry(k).or_insert((v, fa));
+    }
+    if let Some(add_paths) = add_paths {
+        env.insert("PATH".to_string(), add_paths);
+    }
+    // applied by env_with_path once the full PATH is assembled
+    if let Some(remove_paths) = remove_paths {
+        env.insert("MISE_REMOVE_PATH".to_string(), remove_paths);
+    }
+    env
+}
+
+/// the config's env overrides the tools' env unless the var is in tool_override
+fn merge_config_env(
+    entries: &mut BTreeMap<String, (String, EnvSource)>,
+    config_env: &IndexMap<String, String>,
+    tool_override: &BTreeSet<String>,
+) {
+    for (k, v) in config_env {
+        let set_by_tool = matches!(entries.get(k), Some((_, EnvSource::Tool(_))));
+        if set_by_tool && tool_override.contains(k) {
+            continue;
+        }
+        entries.insert(k.clone(), (v.clone(), EnvSource::Config));
+    }
+}
+
+/// finds env vars set to different values by different tools
+/// entries are (key, value, tool id) in toolset order, the first tool to set a key wins
+fn env_conflicts(entries: &[(String, String, ForgeArg)]) -> Vec<String> {
+    let mut seen: HashMap<&str, (&ForgeArg, &str)> = HashMap::new();
+    let mut conflicts = vec![];
+    for (k, v, id) in entries {
+        if matches!(
+            k.as_str(),
+            "MISE_ADD_PATH" | "RTX_ADD_PATH" | "MISE_REMOVE_PATH" | "RTX_REMOVE_PATH"
+        ) {
+            continue;
+        }
+        match seen.get(k.as_str()) {
+            Some(&(winner, winner_v)) if winner != id && winner_v != v.as_str() => {
+                conflicts.push(format!(
+                    "{k} is set by both {winner} ({winner_v}) and {id} ({v}), using {winner_v}"
+                ));
+            }
+            Some(_) => {}
+            None => {
+                seen.insert(k.as_str(), (id, v.as_str()));
+            }
+        }
+    }
+    conflicts
+}
+
 fn get_leaf_dependencies(requests: &[ToolRequest]) -> eyre::Result<Vec<&ToolRequest>> {
     let versions_hash = requests.iter().map(|tr| tr.forge()).collect::<HashSet<_>>();
     let leaves = requests
@@ -535,3 +1959,1332 @@ fn get_leaf_dependencies(requests: &[ToolRequest]) -> eyre::Result<Vec<&ToolRequ
         .collect::<Result<Vec<_>>>()?;
     Ok(leaves)
 }
+
+/// resolves a single request without building a whole Toolset, e.g.: for `mise where node@20`
+/// unlike Toolset::resolve this doesn't check for missing plugins
+pub fn resolve_request(tr: &ToolRequest, opts: &ResolveOptions) -> Result<ToolVersion> {
+    resolve_request_with(forge::get(tr.forge()).as_ref(), tr, opts)
+}
+
+fn resolve_request_with(
+    t: &dyn Forge,
+    tr: &ToolRequest,
+    opts: &ResolveOptions,
+) -> Result<ToolVersion> {
+    tr.resolve(t, opts.latest_versions, opts.prefer_stable)
+}
+
+/// the order tools will be installed in, tools in each round are installed concurrently
+/// after the previous round, e.g.: `[[node], [npm:prettier]]`
+pub fn installation_order(requests: &[ToolRequest]) -> Result<Vec<Vec<ForgeArg>>> {
+    installation_order_with(requests, |tr| tr.dependencies())
+}
+
+fn installation_order_with(
+    requests: &[ToolRequest],
+    dependencies: impl Fn(&ToolRequest) -> Result<Vec<ForgeArg>>,
+) -> Result<Vec<Vec<ForgeArg>>> {
+    check_dependency_cycles(requests, &dependencies)?;
+    let mut remaining = requests
+        .iter()
+        .map(|tr| Ok((tr.forge(), dependencies(tr)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let mut rounds = vec![];
+    while !remaining.is_empty() {
+        // same as get_leaf_dependencies: a tool is a leaf if none of its dependencies are pending
+        let pending = remaining.iter().map(|(fa, _)| *fa).collect::<HashSet<_>>();
+        let (leaves, rest): (Vec<_>, Vec<_>) = remaining
+            .into_iter()
+            .partition(|(_, deps)| deps.iter().all(|dep| !pending.contains(dep)));
+        if leaves.is_empty() {
+            bail!("unable to determine installation order");
+        }
+        rounds.push(
+            leaves
+                .into_iter()
+                .map(|(fa, _)| fa.clone())
+                .unique()
+                .collect(),
+        );
+        remaining = rest;
+    }
+    Ok(rounds)
+}
+
+/// the tools installed at each level of install_versions_with_progress recursing into leaf
+/// dependencies, errors if the recursion goes deeper than max_depth before it overflows the stack
+fn install_chain(
+    chain: &[String],
+    versions: &[ToolRequest],
+    max_depth: usize,
+) -> Result<Vec<String>> {
+    let level = versions.iter().map(|tr| tr.forge()).unique().join(", ");
+    let chain = chain.iter().cloned().chain([level]).collect_vec();
+    ensure!(
+        chain.len() <= max_depth,
+        "dependencies are nested more than {max_depth} levels deep: {}",
+        chain.join(" -> ")
+    );
+    Ok(chain)
+}
+
+/// errors if the requested tools depend on each other in a cycle since install_versions would
+/// otherwise wait on the dependencies forever
+fn check_dependency_cycles(
+    requests: &[ToolRequest],
+    dependencies: impl Fn(&ToolRequest) -> Result<Vec<ForgeArg>>,
+) -> Result<()> {
+    let mut deps: IndexMap<&ForgeArg, Vec<ForgeArg>> = IndexMap::new();
+    for tr in requests {
+        let tr_deps = dependencies(tr)?;
+        deps.entry(tr.forge()).or_default().extend(tr_deps);
+    }
+    fn visit<'a>(
+        fa: &'a ForgeArg,
+        deps: &'a IndexMap<&ForgeArg, Vec<ForgeArg>>,
+        path: &mut Vec<&'a ForgeArg>,
+        done: &mut HashSet<&'a ForgeArg>,
+    ) -> Option<String> {
+        if let Some(i) = path.iter().position(|p| *p == fa) {
+            return Some(path[i..].iter().chain([&fa]).join(" -> "));
+        }
+        if done.contains(fa) {
+            return None;
+        }
+        path.push(fa);
+        for dep in deps.get(fa).into_iter().flatten() {
+            if let Some(cycle) = visit(dep, deps, path, done) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.insert(fa);
+        None
+    }
+    let mut done = HashSet::new();
+    if let Some(cycle) = deps
+        .keys()
+        .find_map(|fa| visit(fa, &deps, &mut vec![], &mut done))
+    {
+        bail!("dependency cycle detected: {cycle}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::CmdLineRunner;
+    use crate::forge::mock::MockForge;
+    use crate::{dirs, file};
+
+    use super::*;
+
+    /// remote versions which sort differently as strings than as versions
+    fn mock_forge(id: &str) -> MockForge {
+        MockForge::new(id).with_remote_versions(&["1.10.0", "2.0.0", "1.9.0"])
+    }
+
+    fn node_forge(id: &str) -> MockForge {
+        MockForge::new(id).with_remote_versions(&[
+            "18.20.2",
+            "20.11.1",
+            "20.12.2",
+            "20.13.0-rc.1",
+            "22.0.0",
+        ])
+    }
+
+    /// a forge for which 1.0.0 is installed, it's added to ts already resolved
+    fn add_installed(ts: &mut Toolset, forge: MockForge) -> Arc<MockForge> {
+        let forge = Arc::new(
+            forge
+                .with_remote_versions(&["1.0.0"])
+                .with_installed_versions(&["1.0.0"]),
+        );
+        forge::insert(forge.clone());
+        let tr = ToolRequest::new(forge.fa().clone(), "1.0.0").unwrap();
+        ts.add_version(tr.clone());
+        let tv = ToolVersion::new(forge.as_ref(), tr, "1.0.0".into());
+        ts.versions.get_mut(forge.fa()).unwrap().versions.push(tv);
+        forge
+    }
+
+    #[test]
+    fn test_pick_job_forge_jobs() {
+        let job = |s: &str| (forge::get(&s.into()), vec![]);
+        let mut queue = vec![job("npm:a"), job("npm:b"), job("cargo:c")];
+        let mut installing = HashMap::new();
+        let forge_jobs = BTreeMap::from([(ForgeType::Npm, 1)]);
+        let mut pick = |installing: &mut HashMap<String, ForgeType>| {
+            pick_job(&mut queue, installing, &forge_jobs).map(|(t, _)| t.id().to_string())
+        };
+        assert_eq!(pick(&mut installing), Some("cargo:c".into()));
+        assert_eq!(pick(&mut installing), Some("npm:b".into()));
+        // npm:a must wait until npm:b is done
+        assert_eq!(pick(&mut installing), None);
+        installing.remove("npm:b");
+        assert_eq!(pick(&mut installing), Some("npm:a".into()));
+    }
+
+    #[test]
+    fn test_sort_versions() {
+        let versions = ["1.10.0", "1.10.0-rc1", "1.9.0", "1.9.0+build"];
+        assert_eq!(
+            sort_versions(versions.map(String::from).to_vec()),
+            vec!["1.9.0", "1.9.0+build", "1.10.0-rc1", "1.10.0"]
+        );
+    }
+
+    #[test]
+    fn test_install_chain() {
+        let linear_chain = |len: usize| {
+            (0..len).try_fold(vec![], |chain, i| {
+                let tr = ToolRequest::new(format!("tool-{i}").as_str().into(), "1").unwrap();
+                install_chain(&chain, &[tr], MAX_INSTALL_DEPTH)
+            })
+        };
+        assert_eq!(
+            linear_chain(MAX_INSTALL_DEPTH).unwrap().len(),
+            MAX_INSTALL_DEPTH
+        );
+        let err = linear_chain(MAX_INSTALL_DEPTH + 10).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("dependencies are nested more than 50 levels deep: tool-0 -> tool-1 -> "));
+    }
+
+    #[test]
+    fn test_merge_request_set() {
+        let mut trs = ToolRequestSet::new();
+        let tiny_source = ToolSource::MiseToml("/tmp/.mise.toml".into());
+        let dummy_source = ToolSource::Environment("MISE_DUMMY_VERSION".into(), "1".into());
+        trs.add_version(ToolRequest::new("tiny".into(), "2").unwrap(), &tiny_source);
+        trs.add_version(
+            ToolRequest::new("dummy".into(), "1").unwrap(),
+            &dummy_source,
+        );
+        let mut ts = Toolset::default();
+        ts.merge_request_set(trs);
+        assert!(matches!(
+            &ts.versions[&ForgeArg::from("tiny")].source,
+            ToolSource::MiseToml(path) if path.to_str() == Some("/tmp/.mise.toml")
+        ));
+        assert!(matches!(
+            &ts.versions[&ForgeArg::from("dummy")].source,
+            ToolSource::Environment(k, _) if k == "MISE_DUMMY_VERSION"
+        ));
+    }
+
+    #[test]
+    fn test_from_sets() {
+        let request_set = |source: &ToolSource, versions: &[(&str, &str)]| {
+            let mut trs = ToolRequestSet::new();
+            for (tool, v) in versions {
+                trs.add_version(ToolRequest::new((*tool).into(), v).unwrap(), source);
+            }
+            trs
+        };
+        let global = ToolSource::MiseToml("/tmp/global/config.toml".into());
+        let project = ToolSource::MiseToml("/tmp/project/.mise.toml".into());
+        let ts = Toolset::from_sets(vec![
+            request_set(&global, &[("tiny", "1"), ("dummy", "1")]),
+            request_set(&project, &[("tiny", "2"), ("dummy", "2")]),
+            request_set(&ToolSource::Argument, &[("tiny", "3")]),
+        ]);
+        let requests = |tool: &str| ts.versions[&ForgeArg::from(tool)].requests.iter().join(" ");
+        assert_str_eq!(requests("tiny"), "tiny@3");
+        assert_str_eq!(requests("dummy"), "dummy@2");
+        assert!(matches!(
+            ts.versions[&ForgeArg::from("tiny")].source,
+            ToolSource::Argument
+        ));
+        assert!(matches!(
+            &ts.versions[&ForgeArg::from("dummy")].source,
+            ToolSource::MiseToml(path) if path.starts_with("/tmp/project")
+        ));
+    }
+
+    #[test]
+    fn test_merge_exec_env_dedupe_add_path() {
+        let entry = |k: &str, v: &str, id: &str| -> (String, String, ForgeArg) {
+            (k.to_string(), v.to_string(), id.into())
+        };
+        let entries = vec![
+            entry("MISE_ADD_PATH", "/shared:/a", "tiny"),
+            entry("MISE_ADD_PATH", "/shared", "dummy"),
+            entry("RTX_ADD_PATH", "/b:/a", "dummy"),
+        ];
+        let env = merge_exec_env(entries);
+        assert_str_eq!(env["PATH"].0, "/shared:/a:/b");
+    }
+
+    #[test]
+    fn test_merge_config_env() {
+        let tool_env = || {
+            BTreeMap::from([(
+                "GOROOT".to_string(),
+                ("/go/1.22".to_string(), EnvSource::Tool("go".into())),
+            )])
+        };
+        let config_env = IndexMap::from([
+            ("GOROOT".to_string(), "/usr/local/go".to_string()),
+            ("FOO".to_string(), "bar".to_string()),
+        ]);
+
+        let mut env = tool_env();
+        merge_config_env(&mut env, &config_env, &BTreeSet::new());
+        assert_eq!(
+            env["GOROOT"],
+            ("/usr/local/go".to_string(), EnvSource::Config)
+        );
+        assert_eq!(env["FOO"], ("bar".to_string(), EnvSource::Config));
+
+        let mut env = tool_env();
+        let tool_override = BTreeSet::from(["GOROOT".to_string(), "FOO".to_string()]);
+        merge_config_env(&mut env, &config_env, &tool_override);
+        assert_eq!(env["GOROOT"], tool_env()["GOROOT"]);
+        // vars which no tool sets still come from the config
+        assert_eq!(env["FOO"], ("bar".to_string(), EnvSource::Config));
+    }
+
+    #[test]
+    fn test_env_conflicts() {
+        let entry = |k: &str, v: &str, id: &str| -> (String, String, ForgeArg) {
+            (k.to_string(), v.to_string(), id.into())
+        };
+        let entries = vec![
+            entry("FOO", "1", "tiny"),
+            entry("BAR", "1", "tiny"),
+            entry("FOO", "2", "dummy"),
+            entry("BAR", "1", "dummy"),
+            entry("MISE_ADD_PATH", "/a", "tiny"),
+            entry("MISE_ADD_PATH", "/b", "dummy"),
+        ];
+        assert_eq!(
+            env_conflicts(&entries),
+            vec!["FOO is set by both tiny (1) and dummy (2), using 1"]
+        );
+    }
+
+    #[test]
+    fn test_merge_exec_env() {
+        let entry = |k: &str, v: &str, id: &str| -> (String, String, ForgeArg) {
+            (k.to_string(), v.to_string(), id.into())
+        };
+        // tiny is listed before dummy in the toolset
+        let entries = vec![
+            entry("FOO", "tiny", "tiny"),
+            entry("MISE_ADD_PATH", "/a", "tiny"),
+            entry("FOO", "dummy", "dummy"),
+            entry("BAR", "dummy", "dummy"),
+            entry("MISE_ADD_PATH", "/b", "dummy"),
+        ];
+        let env = merge_exec_env(entries);
+        assert_str_eq!(env["FOO"].0, "tiny");
+        assert_str_eq!(env["BAR"].0, "dummy");
+        assert_str_eq!(env["PATH"].0, "/a:/b");
+        assert_str_eq!(env["PATH"].1.id, "tiny");
+    }
+
+    #[test]
+    fn test_resolve_request() {
+        let forge = node_forge("mock-node");
+        file::remove_all(&forge.fa().cache_path).unwrap();
+        let tr = ToolRequest::new(forge.fa().clone(), "20").unwrap();
+        let tv = resolve_request_with(&forge, &tr, &ResolveOptions::default()).unwrap();
+        assert_str_eq!(tv.version, "20.12.2");
+        let opts = ResolveOptions {
+            prefer_stable: false,
+            ..Default::default()
+        };
+        let tv = resolve_request_with(&forge, &tr, &opts).unwrap();
+        assert_str_eq!(tv.version, "20.13.0-rc.1");
+    }
+
+    #[test]
+    fn test_update_entry() {
+        let forge = node_forge("mock-node-update");
+        file::remove_all(&forge.fa().cache_path).unwrap();
+        let tr = ToolRequest::new(forge.fa().clone(), "20").unwrap();
+        let tv = ToolVersion::new(&forge, tr, "20.11.1".into());
+        let latest = tv.latest_version(&forge).unwrap();
+        let entry = UpdateEntry::new(Arc::new(forge), tv, latest);
+        assert!(entry.update_available);
+        assert_eq!(entry.bump, BumpKind::Minor);
+        assert_str_eq!(
+            serde_json::to_string(&entry).unwrap(),
+            r#"{"bump":"minor","current":"20.11.1","latest":"20.12.2","requested":"20","update_available":true}"#
+        );
+    }
+
+    #[test]
+    fn test_catch_install_panic() {
+        let forge = MockForge::new("mock-panic").with_install(|_| panic!("oops"));
+        let tr = ToolRequest::new(forge.fa().clone(), "1.0.0").unwrap();
+        let ts = Toolset::default();
+        let ctx = InstallContext {
+            ts: &ts,
+            pr: MultiProgressReport::get().add("mock-panic"),
+            tv: ToolVersion::new(&forge, tr.clone(), "1.0.0".into()),
+            force: false,
+            offline: false,
+            downloads: &DownloadBudget::default(),
+            deadline: None,
+        };
+        let err = catch_install_panic(&tr, || install_version(&forge, &ctx)).unwrap_err();
+        assert_str_eq!(
+            err.to_string(),
+            "panicked while installing mock-panic@1.0.0: oops"
+        );
+        file::remove_all(&forge.fa().installs_path).unwrap();
+    }
+
+    #[test]
+    fn test_max_download_size() {
+        let downloads = DownloadBudget::new(Some(1000));
+        let ts = Toolset::default();
+        let install = |forge: &MockForge| {
+            let tr = ToolRequest::new(forge.fa().clone(), "1.0.0").unwrap();
+            let ctx = InstallContext {
+                ts: &ts,
+                pr: MultiProgressReport::get().add(forge.id()),
+                tv: ToolVersion::new(forge, tr, "1.0.0".into()),
+                force: false,
+                offline: false,
+                downloads: &downloads,
+                deadline: None,
+            };
+            forge.install_version_impl(&ctx)
+        };
+        let forge = MockForge::new("mock-download-a").with_install(|ctx| ctx.add_download(600));
+        install(&forge).unwrap();
+        let tv = ToolVersion::new(
+            &forge,
+            ToolRequest::new(forge.fa().clone(), "1.0.0").unwrap(),
+            "1.0.0".into(),
+        );
+        assert_eq!(downloads.downloaded(&tv), Some(600));
+        let err =
+            install(&MockForge::new("mock-download-b").with_install(|ctx| ctx.add_download(600)))
+                .unwrap_err();
+        assert_str_eq!(
+            err.to_string(),
+            "mock-download-b@1.0.0 brings the total download size to 1200 bytes which exceeds max_download_size (1000 bytes)"
+        );
+    }
+
+    #[test]
+    fn test_install_timeout() {
+        let ts = Toolset::default();
+        let downloads = DownloadBudget::default();
+        let timeout = Duration::from_millis(200);
+        let install = |forge: &MockForge| {
+            let tr = ToolRequest::new(forge.fa().clone(), "1.0.0").unwrap();
+            let tv = ToolVersion::new(forge, tr, "1.0.0".into());
+            file::remove_all(&forge.fa().installs_path).unwrap();
+            let ctx = InstallContext {
+                ts: &ts,
+                pr: MultiProgressReport::get().add(forge.id()),
+                tv: tv.clone(),
+                force: false,
+                offline: false,
+                downloads: &downloads,
+                deadline: Some(Instant::now() + timeout),
+            };
+            (install_version(forge, &ctx), tv)
+        };
+
+        // the command is killed at the deadline and only then is the install removed
+        let forge = MockForge::new("mock-timeout-hung").with_install(|ctx| {
+            CmdLineRunner::new("sleep")
+                .arg("10")
+                .with_deadline(ctx.deadline)
+                .execute()
+        });
+        let start = Instant::now();
+        let (result, tv) = install(&forge);
+        let elapsed = start.elapsed();
+        assert!(elapsed < Duration::from_secs(5));
+        let err = result.unwrap_err().to_string();
+        let reported = err
+            .strip_prefix("timed out installing mock-timeout-hung@1.0.0 after ")
+            .unwrap();
+        // the time the attempt took rather than the configured timeout
+        let reported = match reported.strip_suffix("ms") {
+            Some(ms) => ms.parse::<f64>().unwrap() / 1000.0,
+            None => reported.strip_suffix('s').unwrap().parse().unwrap(),
+        };
+        assert!(reported > 0.15 && reported <= elapsed.as_secs_f64());
+        assert!(!tv.install_path().exists());
+
+        // an install which finishes after the deadline without being stopped is kept
+        let forge = MockForge::new("mock-timeout-slow").with_install(|_| {
+            sleep(Duration::from_millis(500));
+            Ok(())
+        });
+        let (result, tv) = install(&forge);
+        result.unwrap();
+        assert!(tv.install_path().exists());
+        file::remove_all(&forge.fa().installs_path).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_latest_for_install() {
+        let forge = Arc::new(mock_forge("mock-latest"));
+        file::remove_all(&forge.fa().cache_path).unwrap();
+        let trs = ["1", "latest", "2", "ref:main"]
+            .map(|v| ToolRequest::new(forge.fa().clone(), v).unwrap())
+            .to_vec();
+        let t: AForge = forge.clone();
+        let queue = vec![(t, trs.clone())];
+        let opts = InstallOptions {
+            latest_versions: true,
+            ..InstallOptions::new()
+        };
+        let resolved = resolve_latest_for_install(&queue, &opts);
+        assert_eq!(forge.remote_calls.load(Ordering::Relaxed), 1);
+        // "latest" is left to the forge's latest_stable_version in the workers
+        assert!(!resolved.contains_key(&trs[1]));
+        let versions = [&trs[0], &trs[2], &trs[3]]
+            .iter()
+            .map(|tr| resolved[*tr].version.clone())
+            .collect_vec();
+        assert_eq!(versions, ["1.10.0", "2.0.0", "ref:main"]);
+
+        let opts = InstallOptions::new();
+        assert!(resolve_latest_for_install(&queue, &opts).is_empty());
+        assert_eq!(forge.remote_calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_which_cached() {
+        let mut ts = Toolset::new(ToolSource::Argument);
+        let forge = add_installed(
+            &mut ts,
+            MockForge::new("mock-which").with_bins(&["mock-which"]),
+        );
+
+        let (_, tv) = ts.which("mock-which").unwrap();
+        assert_eq!(tv.version, "1.0.0");
+        let calls = forge.which_calls.load(Ordering::Relaxed);
+        assert!(ts.which("mock-which").is_some());
+        assert!(ts.which("mock-which").is_some());
+        assert_eq!(forge.which_calls.load(Ordering::Relaxed), calls);
+
+        // misses are cached too
+        assert!(ts.which("mock-missing").is_none());
+        let calls = forge.which_calls.load(Ordering::Relaxed);
+        assert!(ts.which("mock-missing").is_none());
+        assert_eq!(forge.which_calls.load(Ordering::Relaxed), calls);
+
+        ts.which_cache.invalidate();
+        assert!(ts.which("mock-which").is_some());
+        assert!(forge.which_calls.load(Ordering::Relaxed) > calls);
+
+        // replacing the requests drops the resolved version which provided the bin
+        let mut trs = ToolRequestSet::new();
+        trs.add_version(
+            ToolRequest::new(forge.fa().clone(), "2.0.0").unwrap(),
+            &ToolSource::Argument,
+        );
+        ts.merge_request_set(trs);
+        assert!(ts.which("mock-which").is_none());
+    }
+
+    #[test]
+    fn test_which_path_order() {
+        let config = Config::get();
+        let mut ts = Toolset::new(ToolSource::Argument);
+        let b = MockForge::new("mock-path-b").with_bins(&["mock-path"]);
+        let b_tr = ToolRequest::new(b.fa().clone(), "1.0.0").unwrap();
+        let b_bin_path = ToolVersion::new(&b, b_tr, "1.0.0".into())
+            .install_short_path()
+            .join("bin");
+        // a is added to the toolset first but its exec-env puts b's bin dir earlier in PATH
+        let a = MockForge::new("mock-path-a")
+            .with_bins(&["mock-path"])
+            .with_exec_env(&[("MISE_ADD_PATH", b_bin_path.to_str().unwrap())]);
+        let a = add_installed(&mut ts, a);
+        let b = add_installed(&mut ts, b);
+        let a_bin_path = ts.versions[a.fa()].versions[0]
+            .install_short_path()
+            .join("bin");
+        let path = ts.env_with_path(&config).unwrap()["PATH"].clone();
+        let path = split_paths(&path).collect_vec();
+        let position = |bin_path: &PathBuf| path.iter().position(|p| p == bin_path).unwrap();
+        assert!(position(&b_bin_path) < position(&a_bin_path));
+
+        let (p, _) = ts.which("mock-path").unwrap();
+        assert_eq!(p.fa(), b.fa());
+        let found = ts.which_all("mock-path");
+        assert_eq!(
+            found.iter().map(|(p, _)| p.id()).collect_vec(),
+            vec!["mock-path-b", "mock-path-a"]
+        );
+    }
+
+    #[test]
+    fn test_list_remote_versions() {
+        let forge = mock_forge("mock-remote");
+        file::remove_all(&forge.fa().cache_path).unwrap();
+        let versions = list_remote_versions(&forge, false, false).unwrap();
+        assert_eq!(versions, vec!["2.0.0", "1.10.0", "1.9.0"]);
+        assert_eq!(
+            list_remote_versions(&forge, false, false).unwrap(),
+            versions
+        );
+        assert_eq!(forge.remote_calls.load(Ordering::Relaxed), 1);
+        list_remote_versions(&forge, true, false).unwrap();
+        assert_eq!(forge.remote_calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_resolve_offline() {
+        let forge = mock_forge("mock-offline");
+        file::remove_all(&forge.fa().cache_path).unwrap();
+        let opts = InstallOptions {
+            offline: true,
+            ..InstallOptions::new()
+        };
+        let tr = ToolRequest::new(forge.fa().clone(), "1").unwrap();
+        let err = resolve_for_install(&forge, tr.clone(), &opts).unwrap_err();
+        assert_str_eq!(
+            err.to_string(),
+            "required the remote versions of mock-offline but offline, run `mise ls-remote mock-offline` first"
+        );
+
+        write_remote_version_cache(forge.fa(), &["1.9.0", "1.10.0"]);
+        let tv = resolve_for_install(&forge, tr, &opts).unwrap();
+        assert_str_eq!(tv.version, "1.10.0");
+        assert_eq!(forge.remote_calls.load(Ordering::Relaxed), 0);
+    }
+
+    /// what the forge's own remote version cache holds after an ordinary online install
+    fn write_remote_version_cache(fa: &ForgeArg, versions: &[&str]) {
+        let versions = versions.iter().map(|v| v.to_string()).collect_vec();
+        CacheManager::new(fa.cache_path.join("remote_versions.msgpack.z"))
+            .write(&versions)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_install_offline() {
+        let forge = Arc::new(mock_forge("mock-install-offline"));
+        forge::insert(forge.clone());
+        file::remove_all(&forge.fa().cache_path).unwrap();
+        file::remove_all(&forge.fa().installs_path).unwrap();
+        write_remote_version_cache(forge.fa(), &["1.9.0", "1.10.0"]);
+        let config = Config::get();
+        let mut ts = Toolset::default();
+        let opts = InstallOptions {
+            offline: true,
+            ..InstallOptions::new()
+        };
+        let versions = vec![ToolRequest::new(forge.fa().clone(), "1").unwrap()];
+        let installed = ts
+            .install_versions(&config, versions, &MultiProgressReport::get(), &opts)
+            .unwrap();
+        assert_eq!(
+            installed.iter().map(|tv| tv.to_string()).collect_vec(),
+            ["mock-install-offline@1.10.0"]
+        );
+        assert_eq!(forge.remote_calls.load(Ordering::Relaxed), 0);
+        file::remove_all(&forge.fa().installs_path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_to_notify() {
+        let missing = ["tiny", "dummy", "npm:prettier"]
+            .iter()
+            .map(|id| {
+                let fa: ForgeArg = (*id).into();
+                let tr = ToolRequest::new(fa.clone(), "9.9.9").unwrap();
+                ToolVersion::new(forge::get(&fa).as_ref(), tr, "9.9.9".into())
+            })
+            .collect_vec();
+        let status = SettingsStatus {
+            missing_tools: SettingsStatusMissingTools::Always,
+            missing_tools_ignore: vec!["dum*".into(), "npm:*".into()],
+            ..Default::default()
+        };
+        let notify = missing_to_notify(missing.clone(), &status);
+        assert_eq!(
+            notify.iter().map(|tv| tv.to_string()).collect_vec(),
+            vec!["tiny@9.9.9"]
+        );
+
+        let status = SettingsStatus {
+            missing_tools: SettingsStatusMissingTools::Never,
+            ..status
+        };
+        assert!(missing_to_notify(missing, &status).is_empty());
+    }
+
+    #[test]
+    fn test_list_missing_required_versions() {
+        let mut ts = Toolset::new(ToolSource::Argument);
+        let optional = ToolVersionOptions::from([("optional".to_string(), "true".to_string())]);
+        for (fa, options) in [("tiny", Default::default()), ("dummy", optional)] {
+            let fa: ForgeArg = fa.into();
+            let tr = ToolRequest::new_opts(fa.clone(), "9.9.9", options).unwrap();
+            ts.add_version(tr.clone());
+            let tv = ToolVersion::new(forge::get(&fa).as_ref(), tr, "9.9.9".into());
+            ts.versions.get_mut(&fa).unwrap().versions.push(tv);
+        }
+        let ids = |tvs: Vec<ToolVersion>| tvs.into_iter().map(|tv| tv.forge.id).collect_vec();
+        assert_eq!(ids(ts.list_missing_versions()), vec!["tiny", "dummy"]);
+        assert_eq!(ids(ts.list_missing_required_versions()), vec!["tiny"]);
+    }
+
+    #[test]
+    fn test_env_with_sources() {
+        assert_cli!("install", "tiny@3.1.0");
+        let config = Config::get();
+        let options = [("env_TINY_HOME".to_string(), "{install_path}".to_string())].into();
+        let tr = ToolRequest::new_opts("tiny".into(), "3.1.0", options).unwrap();
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.add_version(tr);
+        ts.resolve().unwrap();
+        let env = ts.env_with_sources(&config).unwrap();
+        assert_eq!(env["TINY_HOME"].1, EnvSource::Tool("tiny".into()));
+    }
+
+    #[test]
+    fn test_env_excluding() {
+        assert_cli!("install", "tiny@3.1.0", "dummy@1.0.0");
+        let config = Config::get();
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.add_version(ToolRequest::new("tiny".into(), "3.1.0").unwrap());
+        ts.add_version(ToolRequest::new("dummy".into(), "1.0.0").unwrap());
+        ts.resolve().unwrap();
+        let bin_path = |fa: &str| ts.list_bin_paths_for(&fa.into())[0].clone();
+        let tiny_bin = bin_path("tiny");
+        let dummy_bin = bin_path("dummy");
+        let env = ts.full_env(&config).unwrap();
+        assert!(env.contains_key("JDXCODE_TINY"));
+        let env = ts.env_excluding(&config, &["tiny".into()].into()).unwrap();
+        assert!(!env.contains_key("JDXCODE_TINY"));
+        let paths = split_paths(&env["PATH"]).collect_vec();
+        assert!(!paths.contains(&tiny_bin));
+        assert!(paths.contains(&dummy_bin));
+    }
+
+    #[test]
+    fn test_exec_env_path() {
+        assert_cli!("install", "tiny@3.1.0", "dummy@1.0.0");
+        let config = Config::get();
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.add_version(ToolRequest::new("tiny".into(), "3.1.0").unwrap());
+        ts.add_version(ToolRequest::new("dummy".into(), "1.0.0").unwrap());
+        ts.resolve().unwrap();
+        let path = ts.exec_env_path().unwrap();
+        let paths = split_paths(&path).collect_vec();
+        let tiny_bin = &ts.list_bin_paths_for(&"tiny".into())[0];
+        let dummy_bin = &ts.list_bin_paths_for(&"dummy".into())[0];
+        let pos = |p: &PathBuf| paths.iter().position(|x| x == p).unwrap();
+        assert!(pos(tiny_bin) < pos(dummy_bin));
+
+        // exec-env results don't change with the env built up by earlier calls
+        let env = ts.env_with_sources(&config).unwrap();
+        env::set_var("JDXCODE_TINY", "accumulated");
+        assert_eq!(ts.exec_env_path().unwrap(), path);
+        assert_eq!(ts.env_with_sources(&config).unwrap(), env);
+        assert_str_eq!(env["JDXCODE_TINY"].0, "3.1.0");
+        env::remove_var("JDXCODE_TINY");
+    }
+
+    #[test]
+    fn test_full_env_cached() {
+        invalidate_env_cache().unwrap();
+        let config = Config::get();
+        let mut ts = Toolset::new(ToolSource::Argument);
+        let forge = add_installed(
+            &mut ts,
+            MockForge::new("mock-exec-env").with_exec_env(&[("MOCK_EXEC_ENV", "1")]),
+        );
+
+        let env = ts.full_env(&config).unwrap();
+        assert_str_eq!(env["MOCK_EXEC_ENV"], "1");
+        let env = ts.full_env(&config).unwrap();
+        assert_str_eq!(env["MOCK_EXEC_ENV"], "1");
+        assert_eq!(forge.exec_env_calls.load(Ordering::Relaxed), 1);
+
+        // the vars a cd changes aren't part of the key
+        let pwd = env::var_os("PWD");
+        env::set_var("PWD", "/mise-test-cd");
+        ts.full_env(&config).unwrap();
+        assert_eq!(forge.exec_env_calls.load(Ordering::Relaxed), 1);
+        match pwd {
+            Some(pwd) => env::set_var("PWD", pwd),
+            None => env::remove_var("PWD"),
+        }
+
+        invalidate_env_cache().unwrap();
+        ts.full_env(&config).unwrap();
+        assert_eq!(forge.exec_env_calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_base_path_env_hermetic() {
+        let inherited = [PathBuf::from("/mise-test-unrelated")];
+        let settings = Settings {
+            hermetic_path_base: vec!["/usr/bin".into()],
+            ..Settings::get().as_ref().clone()
+        };
+        let path_env = base_path_env(&settings, &inherited);
+        assert!(path_env.to_vec().contains(&inherited[0]));
+
+        let settings = Settings {
+            hermetic_path: true,
+            ..settings
+        };
+        let mut path_env = base_path_env(&settings, &inherited);
+        path_env.add("/tool/bin".into());
+        assert_eq!(
+            path_env.to_vec(),
+            vec![
+                PathBuf::from("/tool/bin"),
+                dirs::SHIMS.to_path_buf(),
+                PathBuf::from("/usr/bin")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_install_cancelled() {
+        let installs = ["tiny/2.1.0", "tiny/3.0.1"].map(|v| dirs::INSTALLS.join(v));
+        for path in &installs {
+            file::remove_all(path).unwrap();
+        }
+        let config = Config::get();
+        let mut ts = Toolset::default();
+        let opts = InstallOptions::new();
+        opts.cancel.store(true, Ordering::Relaxed);
+        let versions = vec![
+            ToolRequest::new("tiny".into(), "2.1.0").unwrap(),
+            ToolRequest::new("tiny".into(), "3.0.1").unwrap(),
+        ];
+        let mpr = MultiProgressReport::get();
+        let err = ts
+            .install_versions(&config, versions, &mpr, &opts)
+            .unwrap_err();
+        assert_str_eq!(
+            err.to_string(),
+            "install cancelled after installing 0 tools"
+        );
+        assert!(installs.iter().all(|path| !path.exists()));
+    }
+
+    #[test]
+    fn test_tool_options_env() {
+        let fa: ForgeArg = "tiny".into();
+        let p = forge::get(&fa);
+        let tv = |options: &[(&str, &str)]| {
+            let options = options
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            let tr = ToolRequest::new_opts(fa.clone(), "3.1.0", options).unwrap();
+            ToolVersion::new(p.as_ref(), tr, "3.1.0".into())
+        };
+        let tv1 = tv(&[
+            ("env_TINY_HOME", "{install_path}"),
+            ("env_TINY_BIN", "{bin_path}/rtx-tiny@{version}"),
+            ("url", "{ignored}"),
+        ]);
+        let bin_path = list_bin_paths(p.as_ref(), &tv1)[0].clone();
+        assert_eq!(
+            tool_options_env(p.as_ref(), &tv1).unwrap(),
+            vec![
+                (
+                    "TINY_BIN".to_string(),
+                    format!("{}/rtx-tiny@3.1.0", bin_path.display())
+                ),
+                (
+                    "TINY_HOME".to_string(),
+                    tv1.install_path().to_string_lossy().to_string()
+                ),
+            ]
+        );
+
+        let err = tool_options_env(p.as_ref(), &tv(&[("env_TINY_HOME", "{nope}")])).unwrap_err();
+        assert_str_eq!(
+            err.to_string(),
+            "unknown placeholder {nope} in TINY_HOME of tiny@3.1.0"
+        );
+    }
+
+    #[test]
+    fn test_list_installed_versions_tool_filter() {
+        assert_cli!("install", "tiny@2", "dummy@1.1.0");
+        let config = Config::get();
+        let ts = ToolsetBuilder::new()
+            .with_tool_filter(HashSet::from(["tiny".into()]))
+            .build(&config)
+            .unwrap();
+        let versions = ts.list_installed_versions().unwrap();
+        assert!(!versions.is_empty());
+        assert!(versions.iter().all(|(p, _)| p.id() == "tiny"));
+    }
+
+    #[test]
+    fn test_latest_installed() {
+        assert_cli!("install", "tiny@1", "tiny@2", "tiny@3");
+        let config = Config::get();
+        let fa: ForgeArg = "tiny".into();
+        let ts = ToolsetBuilder::new()
+            .with_args(&["tiny@2".parse().unwrap()])
+            .build(&config)
+            .unwrap();
+        assert_str_eq!(ts.latest_installed(&fa).unwrap().version, "2.1.0");
+        let ts = Toolset::default();
+        assert_str_eq!(ts.latest_installed(&fa).unwrap().version, "3.1.0");
+    }
+
+    #[test]
+    fn test_which_with_install_hint() {
+        assert_cli!("install", "tiny@2");
+        assert_cli!("uninstall", "tiny@1.1.0");
+        let config = Config::get();
+        let ts = ToolsetBuilder::new()
+            .with_args(&["tiny@1.1.0".parse().unwrap()])
+            .build(&config)
+            .unwrap();
+        match ts.which_with_install_hint("rtx-tiny") {
+            WhichResult::NotInstalled(tvs) => {
+                assert_eq!(
+                    tvs.iter().map(|tv| tv.to_string()).collect_vec(),
+                    ["tiny@1.1.0"]
+                )
+            }
+            r => panic!("expected NotInstalled, got {r:?}"),
+        }
+        assert!(matches!(
+            ts.which_with_install_hint("nonexistent-bin"),
+            WhichResult::NotFound
+        ));
+    }
+
+    #[test]
+    fn test_resolve_lenient() {
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.add_version(ToolRequest::new("tiny".into(), "9").unwrap());
+        ts.add_version(ToolRequest::new("dummy".into(), "1.0.0").unwrap());
+        let errors = ts.resolve_lenient();
+        assert_eq!(
+            errors.iter().map(|(fa, _)| fa.id.as_str()).collect_vec(),
+            vec!["tiny"]
+        );
+        assert!(ts.versions[&"tiny".into()].versions.is_empty());
+        assert_str_eq!(ts.versions[&"dummy".into()].versions[0].version, "1.0.0");
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.add_version(ToolRequest::new("tiny".into(), "9").unwrap());
+        ts.add_version(ToolRequest::new("dummy".into(), "1").unwrap());
+        let err = ts.resolve().unwrap_err();
+        let Some(Error::ResolveFailed(errors)) = err.downcast_ref() else {
+            panic!("expected ResolveFailed, got {err:?}");
+        };
+        assert_eq!(errors.len(), 1);
+        let (fa, err) = &errors[0];
+        assert_str_eq!(fa.id, "tiny");
+        assert!(matches!(err, ResolveError::VersionNotFound(_)));
+        assert_str_eq!(
+            ResolveError::new(fa, false, eyre!("not found")).to_string(),
+            "[tiny] plugin not installed"
+        );
+    }
+
+    #[test]
+    fn test_env_with_path_remove_path() {
+        assert_cli!("install", "dummy@1.0.0");
+        file::remove_all(dirs::CACHE.join("dummy")).unwrap();
+        let config = Config::get();
+        let hidden = env::PATH[0].clone();
+        let options = BTreeMap::from([(
+            "remove_path".to_string(),
+            hidden.to_string_lossy().to_string(),
+        )]);
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.add_version(ToolRequest::new_opts("dummy".into(), "1.0.0", options).unwrap());
+        ts.resolve().unwrap();
+        let env = ts.env_with_path(&config).unwrap();
+        assert!(!env.contains_key("MISE_REMOVE_PATH"));
+        let path = split_paths(&env["PATH"]).collect_vec();
+        assert!(!path.contains(&hidden));
+        for p in ts.list_paths() {
+            assert!(path.contains(&p));
+        }
+    }
+
+    #[test]
+    fn test_is_up_to_date() {
+        let toolset = |v: &str| {
+            let mut ts = Toolset::new(ToolSource::Argument);
+            ts.add_version(ToolRequest::new("tiny".into(), v).unwrap());
+            ts.resolve().unwrap();
+            ts
+        };
+        let ts = toolset("system");
+        assert!(ts.is_fully_installed());
+        assert!(ts.is_up_to_date());
+
+        // symlinked versions are never outdated even if there is no such remote version
+        file::create_dir_all("../data/tmp/tiny-8").unwrap();
+        assert_cli!("link", "tiny@8.0.0", "../data/tmp/tiny-8");
+        let ts = toolset("8");
+        assert!(ts.is_fully_installed());
+        assert!(ts.is_up_to_date());
+        assert_cli!("uninstall", "tiny@8.0.0");
+
+        assert_cli!("uninstall", "tiny@2.0.1");
+        let ts = toolset("2.0.1");
+        assert!(!ts.is_fully_installed());
+        assert!(!ts.is_up_to_date());
+    }
+
+    #[test]
+    fn test_which_multiple_versions() {
+        assert_cli!("install", "tiny@3.1.0", "tiny@2.1.0");
+        let which = |versions: [&str; 2]| {
+            let mut ts = Toolset::new(ToolSource::Argument);
+            for v in versions {
+                ts.add_version(ToolRequest::new("tiny".into(), v).unwrap());
+            }
+            ts.resolve().unwrap();
+            let (p, tv) = ts.which("rtx-tiny").unwrap();
+            let bin = p.which(&tv, "rtx-tiny").unwrap().unwrap();
+            let paths = ts.list_paths();
+            assert_eq!(Some(paths[0].as_path()), bin.parent());
+            tv.version
+        };
+        // the first requested version wins even if it isn't the highest
+        assert_str_eq!(which(["3.1.0", "2.1.0"]), "3.1.0");
+        assert_str_eq!(which(["2.1.0", "3.1.0"]), "2.1.0");
+    }
+
+    #[test]
+    fn test_which_any_installed() {
+        assert_cli!("install", "tiny@3.1.0");
+        assert_cli!("uninstall", "tiny@2.0.0");
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.add_version(ToolRequest::new("tiny".into(), "2.0.0").unwrap());
+        ts.resolve().unwrap();
+        assert!(ts.which("rtx-tiny").is_none());
+        let (p, tv) = ts.which_any_installed("rtx-tiny").unwrap();
+        assert_eq!(p.id(), "tiny");
+        assert_ne!(tv.version, "2.0.0");
+    }
+
+    #[test]
+    fn test_lockfile() {
+        let tiny: ForgeArg = "tiny".into();
+        let path = dirs::CACHE.join("mise.lock");
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.add_version(ToolRequest::new(tiny.clone(), "2").unwrap());
+        ts.resolve().unwrap();
+        ts.write_lockfile(&path).unwrap();
+        let lockfile = ToolRequestSet::from_lockfile(&path).unwrap();
+        let locked = lockfile.locked(&ts.versions[&tiny].requests[0]).unwrap();
+        assert_str_eq!(locked.version(), ts.versions[&tiny].versions[0].version);
+        assert!(lockfile
+            .locked(&ToolRequest::new(tiny.clone(), "1").unwrap())
+            .is_none());
+
+        file::write(&path, "[tools]\ntiny = [\"2.0.0\"]\n").unwrap();
+        ts.lockfile = Some(ToolRequestSet::from_lockfile(&path).unwrap());
+        ts.resolve().unwrap();
+        assert_str_eq!(ts.versions[&tiny].versions[0].version, "2.0.0");
+        let mut tvl = ts.versions[&tiny].clone();
+        tvl.resolve(true, ts.lockfile.as_ref()).unwrap();
+        assert_eq!(tvl.versions[0].request, tvl.requests[0]);
+
+        file::write(&path, "[tools]\ntiny = [\"2.9.9\"]\n").unwrap();
+        let lockfile = ToolRequestSet::from_lockfile(&path).unwrap();
+        let err = tvl.resolve(false, Some(&lockfile)).unwrap_err();
+        assert!(format!("{err:#}")
+            .contains("tiny@2.9.9 is locked in mise.lock but is no longer available"));
+        file::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_install_versions_keep_going() {
+        let config = Config::get();
+        let mut ts = Toolset::default();
+        let versions = vec![
+            // the dummy plugin fails to install this version on purpose
+            ToolRequest::new("dummy".into(), "other-dummy").unwrap(),
+            ToolRequest::new("tiny".into(), "3.1.0").unwrap(),
+        ];
+        let opts = InstallOptions {
+            keep_going: true,
+            ..InstallOptions::new()
+        };
+        let err = ts
+            .install_versions(&config, versions, &MultiProgressReport::get(), &opts)
+            .unwrap_err();
+        let Some(Error::InstallFailed { installed, failed }) = err.downcast_ref() else {
+            panic!("expected InstallFailed, got {err:?}");
+        };
+        assert_eq!(
+            installed.iter().map(|tv| tv.to_string()).collect_vec(),
+            ["tiny@3.1.0"]
+        );
+        assert_eq!(failed.len(), 1);
+        assert_str_eq!(failed[0].0.to_string(), "dummy@other-dummy");
+    }
+
+    #[test]
+    fn test_install_versions_with_summary() {
+        assert_cli!("install", "tiny@3.1.0", "dummy@1.0.0", "dummy@1.1.0");
+        assert_cli!("uninstall", "dummy@1.0.0", "dummy@1.1.0");
+        let config = Config::get();
+        let mut ts = Toolset::default();
+        let versions = ["dummy@1.0.0", "dummy@1.1.0", "tiny@3.1.0"]
+            .iter()
+            .map(|v| {
+                let (fa, v) = v.split_once('@').unwrap();
+                ToolRequest::new(fa.into(), v).unwrap()
+            })
+            .collect();
+        let mut summary = InstallSummary::default();
+        let mpr = MultiProgressReport::get();
+        ts.install_versions_with_summary(
+            &config,
+            versions,
+            &mpr,
+            &InstallOptions::new(),
+            &mut summary,
+        )
+        .unwrap();
+        assert_eq!(summary.total(), 3);
+        assert_eq!(
+            summary.by_forge(),
+            IndexMap::from([("dummy".into(), (2, 0, 0)), ("tiny".into(), (0, 1, 0))])
+        );
+        assert!(summary
+            .to_string()
+            .starts_with("dummy: 2 installed, tiny: 1 skipped in "));
+        assert_eq!(summary.records.len(), 3);
+        assert!(summary.records.iter().all(|r| r.bytes.is_none()));
+        let dummy = summary.records.iter().find(|r| r.tv.forge.id == "dummy");
+        assert!(dummy.unwrap().duration > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_install_arg_versions_pin() {
+        let config = Config::get();
+        let mut ts = ToolsetBuilder::new()
+            .with_args(&["tiny@2".parse().unwrap(), "dummy@system".parse().unwrap()])
+            .build(&config)
+            .unwrap();
+        let opts = InstallOptions {
+            pin: true,
+            latest_versions: true,
+            ..InstallOptions::new()
+        };
+        let (installed, pinned) = ts.install_arg_versions(&config, &opts).unwrap();
+        // what was installed is pinned, not what the request resolves to afterwards
+        for tv in installed {
+            assert!(pinned.contains(&(tv.forge.clone(), tv.version.clone())));
+        }
+        let pinned = pinned
+            .into_iter()
+            .map(|(fa, v)| format!("{fa}@{v}"))
+            .sorted()
+            .collect_vec();
+        assert_eq!(pinned, ["dummy@system", "tiny@2.1.0"]);
+    }
+
+    #[test]
+    fn test_add_version_disabled() {
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.disable_tools.insert("tiny".into());
+        assert!(!ts.add_version(ToolRequest::new("tiny".into(), "1").unwrap()));
+        assert!(ts.add_version(ToolRequest::new("dummy".into(), "1").unwrap()));
+        assert_eq!(
+            ts.disabled_by(&"tiny".into()),
+            Some("the disable_tools setting")
+        );
+        assert_eq!(ts.disabled_by(&"dummy".into()), None);
+        assert_eq!(ts.versions.keys().collect_vec(), vec![&"dummy".into()]);
+    }
+
+    #[test]
+    fn test_merge_disabled() {
+        // ToolsetBuilder seeds disable_tools from the disable_tools setting before merging configs
+        let mut ts = Toolset {
+            disable_tools: HashSet::from(["npm:left-pad".into()]),
+            ..Default::default()
+        };
+        let mut cf_ts = Toolset::new(ToolSource::Argument);
+        cf_ts.add_version(ToolRequest::new("npm:left-pad".into(), "1").unwrap());
+        cf_ts.add_version(ToolRequest::new("tiny".into(), "1").unwrap());
+        ts.merge(cf_ts.clone(), MergeStrategy::OverrideExisting);
+        ts.merge(cf_ts, MergeStrategy::FillMissing);
+        assert_eq!(ts.versions.keys().collect_vec(), vec![&"tiny".into()]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let global_path = PathBuf::from("~/.tool-versions");
+        let project_path = PathBuf::from("/project/.tool-versions");
+        let toolset = |path: &PathBuf, versions: &[(&str, &str)]| {
+            let mut ts = Toolset::new(ToolSource::ToolVersions(path.clone()));
+            for (tool, v) in versions {
+                ts.add_version(ToolRequest::new((*tool).into(), v).unwrap());
+            }
+            ts
+        };
+        let global = || toolset(&global_path, &[("tiny", "1"), ("dummy", "1")]);
+        let project = || toolset(&project_path, &[("tiny", "2")]);
+        let requests = |ts: &Toolset| {
+            ts.versions
+                .values()
+                .map(|tvl| {
+                    format!(
+                        "{}@{} ({})",
+                        tvl.forge,
+                        tvl.requests[0].version(),
+                        tvl.source
+                    )
+                })
+                .collect_vec()
+        };
+
+        let mut ts = global();
+        ts.merge(project(), MergeStrategy::OverrideExisting);
+        assert_eq!(
+            requests(&ts),
+            vec![
+                "tiny@2 (/project/.tool-versions)",
+                "dummy@1 (~/.tool-versions)"
+            ]
+        );
+        assert_str_eq!(ts.source.unwrap().to_string(), "/project/.tool-versions");
+
+        let mut ts = global();
+        ts.merge(project(), MergeStrategy::FillMissing);
+        assert_eq!(
+            requests(&ts),
+            vec!["tiny@1 (~/.tool-versions)", "dummy@1 (~/.tool-versions)"]
+        );
+        assert_str_eq!(ts.source.unwrap().to_string(), "~/.tool-versions");
+    }
+
+    #[test]
+    fn test_resolve_single() {
+        let tiny: ForgeArg = "tiny".into();
+        let dummy: ForgeArg = "dummy".into();
+        let mut ts = Toolset::new(ToolSource::Argument);
+        ts.add_version(ToolRequest::new(tiny.clone(), "latest").unwrap());
+        ts.add_version(ToolRequest::new(dummy.clone(), "latest").unwrap());
+        // a resolved version which would be replaced if dummy were resolved again
+        let tvl = ts.versions.get_mut(&dummy).unwrap();
+        let tv = ToolVersion::new(
+            forge::get(&dummy).as_ref(),
+            tvl.requests[0].clone(),
+            "untouched".into(),
+        );
+        tvl.versions.push(tv);
+
+        ts.resolve_single(&tiny).unwrap();
+        assert_eq!(ts.versions[&tiny].versions.len(), 1);
+        assert_str_eq!(ts.versions[&dummy].versions[0].version, "untouched");
+    }
+
+    #[test]
+    fn test_truncate_missing() {
+        let versions = format!(
+            "{} {}",
+            style("tiny@3.1.0").blue().for_stderr(),
+            style("工具@1.0.0").blue().for_stderr()
+        );
+        assert_str_eq!(truncate_missing(&versions, 80), versions);
+        let truncated = truncate_missing(&versions, 5);
+        assert_eq!(console::measure_text_width(&truncated), 1);
+        let truncated = truncate_missing(&versions, 30);
+        assert!(console::measure_text_width(&truncated) <= 16);
+        assert!(truncated.contains('…'));
+    }
+
+    #[test]
+    fn test_list_current_versions_by_type() {
+        let mut ts = Toolset::new(ToolSource::Argument);
+        for (fa, v) in [
+            ("cargo:eza", "0.18.0"),
+            ("npm:prettier", "3.2.5"),
+            ("cargo:ripgrep", "14.1.0"),
+            ("tiny", "3.1.0"),
+        ] {
+            let fa: ForgeArg = fa.into();
+            ts.add_version(ToolRequest::new(fa.clone(), v).unwrap());
+            let tvl = ts.versions.get_mut(&fa).unwrap();
+            let tv = ToolVersion::new(forge::get(&fa).as_ref(), tvl.requests[0].clone(), v.into());
+            tvl.versions.push(tv);
+        }
+        let ids = |ft| {
+            ts.list_current_versions_by_type(ft)
+                .into_iter()
+                .map(|(_, tv)| tv.forge.id)
+                .collect_vec()
+        };
+        assert_eq!(ids(ForgeType::Cargo), vec!["cargo:eza", "cargo:ripgrep"]);
+        assert_eq!(ids(ForgeType::Npm), vec!["npm:prettier"]);
+        assert_eq!(ids(ForgeType::Asdf), vec!["tiny"]);
+        assert!(ids(ForgeType::Pipx).is_empty());
+    }
+
+    #[test]
+    fn test_installation_order() {
+        let forges = [
+            MockForge::new("mock-a"),
+            MockForge::new("mock-b").with_dependencies(&["mock-a"]),
+            MockForge::new("mock-c").with_dependencies(&["mock-b", "mock-a"]),
+            MockForge::new("mock-d"),
+        ];
+        let requests = forges
+            .iter()
+            .rev()
+            .map(|f| ToolRequest::new(f.fa().clone(), "1").unwrap())
+            .collect_vec();
+        let order = installation_order_with(&requests, |tr| {
+            let forge = forges.iter().find(|f| f.fa() == tr.forge()).unwrap();
+            forge.get_dependencies(tr)
+        })
+        .unwrap();
+        let order = order
+            .iter()
+            .map(|round| round.iter().join(","))
+            .collect_vec();
+        assert_eq!(order, vec!["mock-d,mock-a", "mock-b", "mock-c"]);
+    }
+
+    #[test]
+    fn test_check_dependency_cycles() {
+        let check = |forges: &[MockForge]| {
+            let requests = forges
+                .iter()
+                .map(|f| ToolRequest::new(f.fa().clone(), "1").unwrap())
+                .collect_vec();
+            check_dependency_cycles(&requests, |tr| {
+                let forge = forges.iter().find(|f| f.fa() == tr.forge()).unwrap();
+                forge.get_dependencies(tr)
+            })
+        };
+        let a = MockForge::new("mock-a").with_dependencies(&["mock-b"]);
+        let b = MockForge::new("mock-b");
+        assert!(check(&[a, b]).is_ok());
+
+        let a = MockForge::new("mock-a").with_dependencies(&["mock-b"]);
+        let b = MockForge::new("mock-b").with_dependencies(&["mock-a"]);
+        let err = check(&[a, b]).unwrap_err();
+        assert_str_eq!(
+            err.to_string(),
+            "dependency cycle detected: mock-a -> mock-b -> mock-a"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use serde_derive::Serialize;
+use versions::SemVer;
+
+use crate::forge::Forge;
+use crate::toolset::ToolVersion;
+
+/// a tool version which is either missing or has a newer version available
+#[derive(Debug, Clone)]
+pub struct OutdatedInfo {
+    pub forge: Arc<dyn Forge>,
+    pub tv: ToolVersion,
+    pub latest: String,
+    pub bump: BumpKind,
+}
+
+impl OutdatedInfo {
+    pub fn new(forge: Arc<dyn Forge>, tv: ToolVersion, latest: String) -> Self {
+        let bump = BumpKind::new(&tv.version, &latest);
+        Self {
+            forge,
+            tv,
+            latest,
+            bump,
+        }
+    }
+}
+
+/// a current tool version alongside the latest version it would move to on refresh
+///
+/// fields are declared in alphabetical order so the serialized JSON is stable
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateEntry {
+    #[serde(skip)]
+    pub forge: Arc<dyn Forge>,
+    #[serde(skip)]
+    pub tv: ToolVersion,
+    pub bump: BumpKind,
+    pub current: String,
+    pub latest: String,
+    pub requested: String,
+    pub update_available: bool,
+}
+
+impl UpdateEntry {
+    pub fn new(forge: Arc<dyn Forge>, tv: ToolVersion, latest: String) -> Self {
+        let update_available = !forge.is_version_installed(&tv) || tv.version != latest;
+        Self {
+            bump: BumpKind::new(&tv.version, &latest),
+            current: tv.version.clone(),
+            requested: tv.request.version(),
+            latest,
+            update_available,
+            forge,
+            tv,
+        }
+    }
+}
+
+impl From<UpdateEntry> for OutdatedInfo {
+    fn from(e: UpdateEntry) -> Self {
+        Self {
+            forge: e.forge,
+            tv: e.tv,
+            latest: e.latest,
+            bump: e.bump,
+        }
+    }
+}
+
+/// how large of a jump it is from the current version to the latest one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BumpKind {
+    Major,
+    Minor,
+    Patch,
+    /// either version is not semver or they are the same
+    Other,
+}
+
+impl BumpKind {
+    pub fn new(current: &str, latest: &str) -> Self {
+        let parse = |v: &str| SemVer::new(v.trim_start_matches('v'));
+        match (parse(current), parse(latest)) {
+            (Some(c), Some(l)) if c.major != l.major => Self::Major,
+            (Some(c), Some(l)) if c.minor != l.minor => Self::Minor,
+            (Some(c), Some(l)) if c.patch != l.patch => Self::Patch,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_kind() {
+        assert_eq!(BumpKind::new("1.2.3", "2.0.0"), BumpKind::Major);
+        assert_eq!(BumpKind::new("1.2.3", "1.3.0"), BumpKind::Minor);
+        assert_eq!(BumpKind::new("v1.2.3", "v1.2.4"), BumpKind::Patch);
+        assert_eq!(BumpKind::new("1.2.3", "1.2.3"), BumpKind::Other);
+        assert_eq!(BumpKind::new("ref:master", "1.2.3"), BumpKind::Other);
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+
+use crate::cli::args::ForgeArg;
+use crate::file;
+use crate::file::display_path;
+
+/// a problem with PATH which makes mise's tools not run as expected, see Toolset::doctor_path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathIssue {
+    ShimsNotOnPath(PathBuf),
+    /// (tool, bin, the earlier PATH entry with the same bin)
+    Shadowed(ForgeArg, String, PathBuf),
+    Duplicate(PathBuf),
+}
+
+impl PathIssue {
+    /// checks `path` for issues with the shims dir and the bin paths of the tools
+    /// a tool's bins are expected at its bin path if that is on PATH, otherwise at the shims dir
+    pub fn find(path: &[PathBuf], shims: &Path, bin_paths: &[(ForgeArg, PathBuf)]) -> Vec<Self> {
+        let mut issues = vec![];
+        if !path.iter().any(|p| p == shims) {
+            issues.push(Self::ShimsNotOnPath(shims.to_path_buf()));
+        }
+        for (fa, bin_path) in bin_paths {
+            let Some(idx) = path
+                .iter()
+                .position(|p| p == bin_path)
+                .or_else(|| path.iter().position(|p| p == shims))
+            else {
+                continue;
+            };
+            for bin in file::ls(bin_path).unwrap_or_default() {
+                if !file::is_executable(&bin) {
+                    continue;
+                }
+                let name = bin.file_name().unwrap().to_string_lossy().to_string();
+                let earlier = path[..idx]
+                    .iter()
+                    .find(|p| file::is_executable(&p.join(&name)));
+                if let Some(earlier) = earlier {
+                    issues.push(Self::Shadowed(fa.clone(), name, earlier.clone()));
+                }
+            }
+        }
+        for p in path.iter().duplicates() {
+            issues.push(Self::Duplicate(p.clone()));
+        }
+        issues
+    }
+}
+
+impl Display for PathIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ShimsNotOnPath(shims) => write!(
+                f,
+                "the shims directory {} is not on PATH, tools will only be found when mise is activated",
+                display_path(shims)
+            ),
+            Self::Shadowed(fa, bin, earlier) => write!(
+                f,
+                "{bin} from {fa} is shadowed by {} which comes earlier in PATH",
+                display_path(earlier.join(bin))
+            ),
+            Self::Duplicate(p) => write!(
+                f,
+                "{} appears in PATH more than once, only the first entry is used",
+                display_path(p)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dirs;
+
+    use super::*;
+
+    #[test]
+    fn test_path_issues() {
+        let dir = dirs::CACHE.join("test-path-issues");
+        let shims = dir.join("shims");
+        let system = dir.join("system");
+        let tiny = dir.join("tiny/bin");
+        for (d, bin) in [
+            (&system, "rtx-tiny"),
+            (&tiny, "rtx-tiny"),
+            (&shims, "rtx-tiny"),
+        ] {
+            file::create_dir_all(d).unwrap();
+            file::write(d.join(bin), "").unwrap();
+            file::make_executable(&d.join(bin)).unwrap();
+        }
+        let bin_paths = [("tiny".into(), tiny.clone())];
+
+        let issues = PathIssue::find(&[shims.clone(), system.clone()], &shims, &bin_paths);
+        assert_eq!(issues, vec![]);
+
+        let issues = PathIssue::find(&[system.clone()], &shims, &bin_paths);
+        assert_eq!(issues, vec![PathIssue::ShimsNotOnPath(shims.clone())]);
+
+        let issues = PathIssue::find(&[system.clone(), shims.clone()], &shims, &bin_paths);
+        assert_eq!(
+            issues,
+            vec![PathIssue::Shadowed(
+                "tiny".into(),
+                "rtx-tiny".into(),
+                system.clone()
+            )]
+        );
+        assert_str_eq!(
+            issues[0].to_string(),
+            format!(
+                "rtx-tiny from tiny is shadowed by {} which comes earlier in PATH",
+                display_path(system.join("rtx-tiny"))
+            )
+        );
+
+        let path = [shims.clone(), system.clone(), shims.clone()];
+        let issues = PathIssue::find(&path, &shims, &bin_paths);
+        assert_eq!(issues, vec![PathIssue::Duplicate(shims.clone())]);
+
+        file::remove_all(&dir).unwrap();
+    }
+}
@@ -1,13 +1,15 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Display};
+use std::path::Path;
 
 use indexmap::IndexMap;
 use itertools::Itertools;
 
 use crate::cli::args::{ForgeArg, ToolArg};
 use crate::config::{Config, Settings};
+use crate::toolset::lockfile::Lockfile;
 use crate::toolset::{ToolRequest, ToolSource};
-use crate::{config, env};
+use crate::{config, env, registry};
 
 #[derive(Debug, Default, Clone)]
 pub struct ToolRequestSet {
@@ -47,6 +49,53 @@ impl ToolRequestSet {
             .collect()
     }
 
+    /// the exact versions from a mise.lock, see Lockfile
+    pub fn from_lockfile(path: &Path) -> eyre::Result<Self> {
+        let mut trs = Self::new();
+        for (id, versions) in Lockfile::read(path)?.tools {
+            let fa: ForgeArg = id.as_str().into();
+            let requests = versions
+                .iter()
+                .map(|v| ToolRequest::new(fa.clone(), v))
+                .collect::<eyre::Result<Vec<_>>>()?;
+            trs.tools.insert(fa, requests);
+        }
+        Ok(trs)
+    }
+
+    /// the locked version matching a request from config, the options of the request are kept
+    /// only plain versions and prefixes are locked, e.g.: "20" or "latest" can be locked to
+    /// "20.12.2" but aliases, refs, and paths are always resolved
+    pub fn locked(&self, tr: &ToolRequest) -> Option<ToolRequest> {
+        let locked = self
+            .tools
+            .get(tr.forge())?
+            .iter()
+            .map(|l| l.version())
+            .find(|l| match tr {
+                ToolRequest::Version { version, .. } => {
+                    version == "latest" || version == l || l.starts_with(&format!("{version}."))
+                }
+                ToolRequest::Prefix { prefix, .. } => l.starts_with(prefix),
+                _ => false,
+            })?;
+        Some(ToolRequest::Version {
+            forge: tr.forge().clone(),
+            version: locked,
+            options: tr.options(),
+        })
+    }
+
+    /// replaces the requests which have a locked version with it, see locked
+    pub fn lock(mut self, lockfile: &ToolRequestSet) -> Self {
+        for tr in self.tools.values_mut().flatten() {
+            if let Some(locked) = lockfile.locked(tr) {
+                *tr = locked;
+            }
+        }
+        self
+    }
+
     pub fn add_version(&mut self, tr: ToolRequest, source: &ToolSource) {
         let fa = tr.forge();
         if !self.tools.contains_key(fa) {
@@ -114,6 +163,7 @@ impl ToolRequestSetBuilder {
             self.load_runtime_args(&mut trs)?;
         }
 
+        let mut trs = expand_registry_entries(trs)?;
         let forges = trs.tools.keys().cloned().collect::<Vec<_>>();
         for fa in &forges {
             if self.is_disabled(fa) {
@@ -190,6 +240,34 @@ impl ToolRequestSetBuilder {
     }
 }
 
+/// replaces tools which are registry entries for several forges with the same requests for each
+/// forge, e.g.: `python-tools = "latest"` -> `pipx:black = "latest"`, `pipx:ruff = "latest"`
+/// forges which are also configured directly keep their own requests
+fn expand_registry_entries(trs: ToolRequestSet) -> eyre::Result<ToolRequestSet> {
+    let mut expanded = ToolRequestSet::new();
+    for (fa, requests) in &trs.tools {
+        let source = &trs.sources[fa];
+        let specs = registry::expand(&fa.id);
+        if specs.len() == 1 {
+            for tr in requests {
+                expanded.add_version(tr.clone(), source);
+            }
+            continue;
+        }
+        for spec in specs {
+            let spec_fa: ForgeArg = spec.as_str().into();
+            if trs.tools.contains_key(&spec_fa) {
+                continue;
+            }
+            for tr in requests {
+                let tr = ToolRequest::new_opts(spec_fa.clone(), &tr.version(), tr.options())?;
+                expanded.add_version(tr, source);
+            }
+        }
+    }
+    Ok(expanded)
+}
+
 fn merge(a: &mut ToolRequestSet, b: ToolRequestSet) {
     for (fa, versions) in b.tools {
         a.tools.insert(fa, versions);
@@ -4,6 +4,11 @@ use std::path::PathBuf;
 use indexmap::{indexmap, IndexMap};
 use serde_derive::Serialize;
 
+use crate::cli::args::ForgeArg;
+use crate::config::config_file::mise_toml::MiseToml;
+use crate::config::config_file::tool_versions::ToolVersions;
+use crate::config::config_file::ConfigFile;
+use crate::file;
 use crate::file::display_path;
 
 /// where a tool version came from (e.g.: .tool-versions)
@@ -29,6 +34,23 @@ impl Display for ToolSource {
 }
 
 impl ToolSource {
+    /// pins fa to version in the file it came from, e.g.: after "20" is resolved to "20.12.2"
+    /// comments and the other tools in the file are kept
+    pub fn update_version(&self, fa: &ForgeArg, version: &str) -> eyre::Result<()> {
+        let mut cf: Box<dyn ConfigFile> = match self {
+            ToolSource::ToolVersions(path) => Box::new(ToolVersions::from_file(path)?),
+            ToolSource::MiseToml(path) => Box::new(MiseToml::from_file(path)?),
+            ToolSource::LegacyVersionFile(path) => {
+                return file::write(path, format!("{version}\n"))
+            }
+            ToolSource::Argument | ToolSource::Environment(..) => {
+                bail!("{fa} is set by {self} which is not a file, can't pin it to {version}")
+            }
+        };
+        cf.replace_versions(fa, &[version.to_string()])?;
+        cf.save()
+    }
+
     pub fn as_json(&self) -> IndexMap<String, String> {
         match self {
             ToolSource::ToolVersions(path) => indexmap! {
@@ -57,6 +79,8 @@ impl ToolSource {
 
 #[cfg(test)]
 mod tests {
+    use crate::dirs;
+
     use super::*;
 
     #[test]
@@ -126,4 +150,44 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_update_version_tool_versions() {
+        let path = dirs::CACHE.join("test-update-version/.tool-versions");
+        file::create_dir_all(path.parent().unwrap()).unwrap();
+        file::write(&path, "# pinned by ci\ntiny 2 # keep\ndummy 1.0.0\n").unwrap();
+        let ts = ToolSource::ToolVersions(path.clone());
+        ts.update_version(&"tiny".into(), "2.1.0").unwrap();
+        assert_str_eq!(
+            file::read_to_string(&path).unwrap(),
+            "# pinned by ci\ntiny  2.1.0 # keep\ndummy 1.0.0\n"
+        );
+        file::remove_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_update_version_mise_toml() {
+        let path = dirs::CACHE.join("test-update-version-toml/.mise.toml");
+        file::create_dir_all(path.parent().unwrap()).unwrap();
+        file::write(
+            &path,
+            "# pinned by ci\n[tools]\ntiny = \"2\"\ndummy = \"1.0.0\"\n",
+        )
+        .unwrap();
+        let ts = ToolSource::MiseToml(path.clone());
+        ts.update_version(&"tiny".into(), "2.1.0").unwrap();
+        assert_str_eq!(
+            file::read_to_string(&path).unwrap(),
+            "# pinned by ci\n[tools]\ntiny = \"2.1.0\"\ndummy = \"1.0.0\"\n"
+        );
+        file::remove_all(path.parent().unwrap()).unwrap();
+
+        let err = ToolSource::Argument
+            .update_version(&"tiny".into(), "2.1.0")
+            .unwrap_err();
+        assert_str_eq!(
+            err.to_string(),
+            "tiny is set by --runtime which is not a file, can't pin it to 2.1.0"
+        );
+    }
 }
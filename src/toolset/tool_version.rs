@@ -6,12 +6,15 @@ use std::path::PathBuf;
 
 use console::style;
 use eyre::Result;
+use itertools::Itertools;
+use semver::VersionReq;
 
 use crate::cli::args::ForgeArg;
-use crate::config::Config;
+use crate::config::{Config, Settings};
+use crate::file;
 use crate::forge;
 use crate::forge::{AForge, Forge};
-use crate::hash::hash_to_str;
+use crate::hash::{hash_to_str, Checksum};
 use crate::toolset::{tool_version_request, ToolRequest, ToolVersionOptions};
 
 /// represents a single version of a tool for a particular plugin
@@ -31,19 +34,36 @@ impl ToolVersion {
         }
     }
 
-    pub fn resolve(tool: &dyn Forge, request: ToolRequest, latest_versions: bool) -> Result<Self> {
+    /// prefer_stable skips prereleases like "2.0.0-rc1" unless requested exactly or there is no
+    /// stable version matching the request
+    pub fn resolve(
+        tool: &dyn Forge,
+        request: ToolRequest,
+        latest_versions: bool,
+        prefer_stable: bool,
+    ) -> Result<Self> {
         if !tool.is_installed() {
             let tv = Self::new(tool, request.clone(), request.version());
             return Ok(tv);
         }
         let tv = match request.clone() {
             ToolRequest::Version { version: v, .. } => {
-                Self::resolve_version(tool, request, latest_versions, &v)?
+                Self::resolve_version(tool, request, latest_versions, prefer_stable, &v)?
+            }
+            ToolRequest::Prefix { prefix, .. } => {
+                Self::resolve_prefix(tool, request, prefer_stable, &prefix)?
             }
-            ToolRequest::Prefix { prefix, .. } => Self::resolve_prefix(tool, request, &prefix)?,
+            ToolRequest::Constraint { req, .. } => Self::resolve_constraint(tool, request, &req)?,
             ToolRequest::Sub {
                 sub, orig_version, ..
-            } => Self::resolve_sub(tool, request, latest_versions, &sub, &orig_version)?,
+            } => Self::resolve_sub(
+                tool,
+                request,
+                latest_versions,
+                prefer_stable,
+                &sub,
+                &orig_version,
+            )?,
             _ => {
                 let version = request.version();
                 Self::new(tool, request, version)
@@ -52,6 +72,44 @@ impl ToolVersion {
         Ok(tv)
     }
 
+    /// the full path of an executable in the bin paths of this version
+    /// on windows "node" also matches "node.exe", "node.cmd", and "node.bat"
+    pub fn bin_path(&self, forge: &dyn Forge, bin: &str) -> Option<PathBuf> {
+        match self.list_bin_paths(forge) {
+            Ok(bin_paths) => find_bin(&bin_paths, bin),
+            Err(err) => {
+                warn!("Error listing bin paths for {self}: {err:#}");
+                None
+            }
+        }
+    }
+
+    /// the forge's bin paths for this version unless the `bin_path` option overrides them
+    pub fn list_bin_paths(&self, forge: &dyn Forge) -> Result<Vec<PathBuf>> {
+        match self.custom_bin_paths() {
+            Some(bin_paths) => Ok(bin_paths),
+            None => forge.list_bin_paths(self),
+        }
+    }
+
+    /// the dirs of the `bin_path` option for tools with an unusual layout, comma-separated and
+    /// relative to the install path, e.g.: `bin_path = "sdk/bin,tools"`
+    pub fn custom_bin_paths(&self) -> Option<Vec<PathBuf>> {
+        let bin_paths = self.request.options().get("bin_path")?.clone();
+        let install_path = self.install_short_path();
+        let bin_paths = bin_paths
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| install_path.join(p))
+            .collect();
+        Some(bin_paths)
+    }
+
+    pub fn is_optional(&self) -> bool {
+        self.request.is_optional()
+    }
+
     pub fn get_forge(&self) -> AForge {
         forge::get(&self.forge)
     }
@@ -81,8 +139,27 @@ impl ToolVersion {
     pub fn download_path(&self) -> PathBuf {
         self.forge.downloads_path.join(self.tv_pathname())
     }
+    /// the `checksum` option, e.g.: `node = { version = "20", checksum = "sha256:abc..." }`
+    pub fn checksum(&self) -> Option<Checksum> {
+        let checksum = self.request.options().get("checksum")?.clone();
+        match checksum.parse() {
+            Ok(checksum) => Some(checksum),
+            Err(err) => {
+                warn!("{self}: {err:#}");
+                None
+            }
+        }
+    }
+    /// where to fetch the tool from if overridden with `tool[repo=...]` or the `repo` option
+    pub fn source_url(&self) -> Option<String> {
+        self.forge
+            .source_url
+            .clone()
+            .or_else(|| self.request.options().get("repo").cloned())
+    }
     pub fn latest_version(&self, tool: &dyn Forge) -> Result<String> {
-        let tv = self.request.resolve(tool, true)?;
+        let prefer_stable = Settings::get().prefer_stable;
+        let tv = self.request.resolve(tool, true, prefer_stable)?;
         Ok(tv.version)
     }
     pub fn style(&self) -> String {
@@ -96,6 +173,7 @@ impl ToolVersion {
         match &self.request {
             ToolRequest::Version { .. } => self.version.to_string(),
             ToolRequest::Prefix { .. } => self.version.to_string(),
+            ToolRequest::Constraint { .. } => self.version.to_string(),
             ToolRequest::Sub { .. } => self.version.to_string(),
             ToolRequest::Ref { ref_: r, .. } => format!("ref-{}", r),
             ToolRequest::Path(_, p) => format!("path-{}", hash_to_str(p)),
@@ -115,6 +193,7 @@ impl ToolVersion {
         tool: &dyn Forge,
         request: ToolRequest,
         latest_versions: bool,
+        prefer_stable: bool,
         v: &str,
     ) -> Result<ToolVersion> {
         let config = Config::get();
@@ -127,11 +206,11 @@ impl ToolVersion {
                 return Self::resolve_path(tool, PathBuf::from(p));
             }
             Some(("prefix", p)) => {
-                return Self::resolve_prefix(tool, request, p);
+                return Self::resolve_prefix(tool, request, prefer_stable, p);
             }
             Some((part, v)) if part.starts_with("sub-") => {
                 let sub = part.split_once('-').unwrap().1;
-                return Self::resolve_sub(tool, request, latest_versions, sub, v);
+                return Self::resolve_sub(tool, request, latest_versions, prefer_stable, sub, v);
             }
             _ => (),
         }
@@ -154,6 +233,12 @@ impl ToolVersion {
                 }
             }
             if let Some(v) = tool.latest_version(None)? {
+                if prefer_stable && is_prerelease(&v) {
+                    let matches = tool.list_versions_matching("latest")?;
+                    if let Some(v) = last_match(&matches, true) {
+                        return build(v.clone());
+                    }
+                }
                 return build(v);
             }
         }
@@ -162,7 +247,7 @@ impl ToolVersion {
             if matches.contains(&v) {
                 return build(v);
             }
-            if let Some(v) = matches.last() {
+            if let Some(v) = last_match(&matches, prefer_stable) {
                 return build(v.clone());
             }
         }
@@ -170,7 +255,7 @@ impl ToolVersion {
         if matches.contains(&v) {
             return build(v);
         }
-        Self::resolve_prefix(tool, request, &v)
+        Self::resolve_prefix(tool, request, prefer_stable, &v)
     }
 
     /// resolve a version like `sub-1:12.0.0` which becomes `11.0.0`, `sub-0.1:12.1.0` becomes `12.0.0`
@@ -178,6 +263,7 @@ impl ToolVersion {
         tool: &dyn Forge,
         request: ToolRequest,
         latest_versions: bool,
+        prefer_stable: bool,
         sub: &str,
         v: &str,
     ) -> Result<Self> {
@@ -186,19 +272,67 @@ impl ToolVersion {
             _ => Config::get().resolve_alias(tool, v)?,
         };
         let v = tool_version_request::version_sub(&v, sub);
-        Self::resolve_version(tool, request, latest_versions, &v)
+        Self::resolve_version(tool, request, latest_versions, prefer_stable, &v)
     }
 
-    fn resolve_prefix(tool: &dyn Forge, request: ToolRequest, prefix: &str) -> Result<Self> {
+    /// resolves a prefix like `20`, `20.1` or glob like `lts-*` to the last matching version
+    fn resolve_prefix(
+        tool: &dyn Forge,
+        request: ToolRequest,
+        prefer_stable: bool,
+        prefix: &str,
+    ) -> Result<Self> {
         let matches = tool.list_versions_matching(prefix)?;
-        let v = match matches.last() {
+        let v = match last_match(&matches, prefer_stable) {
             Some(v) => v,
-            None => prefix,
-            // None => Err(VersionNotFound(plugin.name.clone(), prefix.to_string()))?,
+            None => {
+                // plugins may accept unlisted versions like "nightly" so only numeric prefixes
+                // and globs are errors, and only if the plugin actually lists versions
+                check_unknown_alias(tool, prefix)?;
+                if prefix.starts_with(|c: char| c.is_ascii_digit()) || prefix.contains('*') {
+                    let available = tool.list_remote_versions()?;
+                    if !available.is_empty() {
+                        bail!(
+                            "no versions of {} match {prefix}, closest available: {}",
+                            tool.id(),
+                            closest_versions(&available, prefix).join(", ")
+                        );
+                    }
+                }
+                prefix
+            }
         };
         Ok(Self::new(tool, request, v.to_string()))
     }
 
+    /// resolves a semver constraint like `>=18` to the highest satisfying version
+    fn resolve_constraint(
+        tool: &dyn Forge,
+        request: ToolRequest,
+        req: &VersionReq,
+    ) -> Result<Self> {
+        let versions = tool
+            .list_remote_versions()?
+            .into_iter()
+            .filter_map(|v| Some((tool_version_request::parse_semver(&v)?, v)))
+            .collect_vec();
+        if versions.is_empty() {
+            bail!(
+                "{} does not use semver versions, version constraints like {} are not supported",
+                tool.id(),
+                request.version()
+            );
+        }
+        let Some((_, v)) = versions
+            .into_iter()
+            .filter(|(sv, _)| req.matches(sv))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+        else {
+            bail!("no versions of {} satisfy {}", tool.id(), request.version());
+        };
+        Ok(Self::new(tool, request, v))
+    }
+
     fn resolve_ref(tool: &dyn Forge, ref_: String, opts: ToolVersionOptions) -> Self {
         let request = ToolRequest::Ref {
             forge: tool.fa().clone(),
@@ -217,6 +351,82 @@ impl ToolVersion {
     }
 }
 
+/// errors if v looks like an alias of the tool but isn't one, e.g.: `lts/foo` when only
+/// `lts` and `lts/hydrogen` exist. only the `/`-separated namespace of an alias is checked so
+/// unlisted versions like `nightly-2024-05-01` still pass through with a `nightly` alias
+fn check_unknown_alias(tool: &dyn Forge, v: &str) -> Result<()> {
+    let Some((namespace, _)) = v.split_once('/') else {
+        return Ok(());
+    };
+    let known = Config::get().alias_names(tool)?;
+    let in_namespace = |a: &String| {
+        a == namespace
+            || a.strip_prefix(namespace)
+                .is_some_and(|rest| rest.starts_with('/'))
+    };
+    if known.iter().any(in_namespace) {
+        bail!(
+            "unknown alias {v} for {}, known aliases: {}",
+            tool.id(),
+            known.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// the last version in matches, skipping prereleases if prefer_stable and a stable version exists
+fn last_match(matches: &[String], prefer_stable: bool) -> Option<&String> {
+    match prefer_stable {
+        true => matches
+            .iter()
+            .rev()
+            .find(|v| !is_prerelease(v))
+            .or(matches.last()),
+        false => matches.last(),
+    }
+}
+
+/// loose check which also works for non-semver tags like "1.2.0beta1" or "2.0.dev0", only whole
+/// words between digits and separators count so e.g.: "devel" or "source" aren't prereleases
+fn is_prerelease(v: &str) -> bool {
+    let v = v.to_lowercase();
+    v.split(|c: char| !c.is_ascii_alphabetic())
+        .any(|word| ["alpha", "beta", "rc", "dev", "pre"].contains(&word))
+}
+
+/// the first executable named bin in bin_paths, see ToolVersion::bin_path
+pub fn find_bin(bin_paths: &[PathBuf], bin: &str) -> Option<PathBuf> {
+    let mut names = vec![bin.to_string()];
+    if cfg!(windows) {
+        names.extend(["exe", "cmd", "bat"].map(|ext| format!("{bin}.{ext}")));
+    }
+    bin_paths
+        .iter()
+        .flat_map(|dir| names.iter().map(|name| dir.join(name)))
+        .find(|path| file::is_executable(path))
+}
+
+/// the last 5 versions sharing the longest common prefix with query
+fn closest_versions<'a>(available: &'a [String], query: &str) -> Vec<&'a str> {
+    let common = |v: &str| {
+        v.chars()
+            .zip(query.chars())
+            .take_while(|(a, b)| a == b)
+            .count()
+    };
+    let best = available
+        .iter()
+        .map(|v| common(v))
+        .max()
+        .unwrap_or_default();
+    let closest = available
+        .iter()
+        .filter(|v| common(v) == best)
+        .map(|v| v.as_str())
+        .collect::<Vec<_>>();
+    closest[closest.len().saturating_sub(5)..].to_vec()
+}
+
 impl Display for ToolVersion {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}@{}", &self.forge.id, &self.version)
@@ -252,3 +462,178 @@ impl Hash for ToolVersion {
         self.version.hash(state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::forge::mock::MockForge;
+
+    use super::*;
+
+    #[test]
+    fn test_bin_path() {
+        let forge = MockForge::new("mock-bin");
+        let fa = forge.fa().clone();
+        let tr = ToolRequest::new(fa, "1.0.0").unwrap();
+        let tv = ToolVersion::new(&forge, tr, "1.0.0".into());
+        let bin_dir = forge.list_bin_paths(&tv).unwrap()[0].clone();
+        file::create_dir_all(&bin_dir).unwrap();
+        for name in ["node", "node.exe"] {
+            file::write(bin_dir.join(name), "").unwrap();
+            file::make_executable(&bin_dir.join(name)).unwrap();
+        }
+        assert_eq!(tv.bin_path(&forge, "node"), Some(bin_dir.join("node")));
+        assert_eq!(tv.bin_path(&forge, "npm"), None);
+
+        file::remove_file(bin_dir.join("node")).unwrap();
+        let expected = cfg!(windows).then(|| bin_dir.join("node.exe"));
+        assert_eq!(tv.bin_path(&forge, "node"), expected);
+        file::remove_all(tv.install_path()).unwrap();
+    }
+
+    #[test]
+    fn test_custom_bin_paths() {
+        let forge = MockForge::new("mock-bin-path");
+        let fa = forge.fa().clone();
+        let tr = ToolRequest::new(fa.clone(), "1.0.0").unwrap();
+        let tv = ToolVersion::new(&forge, tr, "1.0.0".into());
+        let install_path = tv.install_short_path();
+        assert_eq!(
+            tv.list_bin_paths(&forge).unwrap(),
+            vec![install_path.join("bin")]
+        );
+
+        let options = [("bin_path".to_string(), "sdk/bin, /opt/tools".to_string())].into();
+        let tr = ToolRequest::new_opts(fa, "1.0.0", options).unwrap();
+        let tv = ToolVersion::new(&forge, tr, "1.0.0".into());
+        assert_eq!(
+            tv.list_bin_paths(&forge).unwrap(),
+            vec![install_path.join("sdk/bin"), PathBuf::from("/opt/tools")]
+        );
+    }
+
+    #[test]
+    fn test_resolve_prefix() {
+        let forge = MockForge::new("mock-prefix").with_remote_versions(&[
+            "20.0.0",
+            "20.1.0",
+            "21.0.0",
+            "lts-gallium",
+            "lts-hydrogen",
+        ]);
+        let fa = forge.fa().clone();
+        let resolve = |v: &str| {
+            ToolRequest::new(fa.clone(), v)
+                .unwrap()
+                .resolve(&forge, false, true)
+                .map(|tv| tv.version)
+        };
+        assert_str_eq!(resolve("20").unwrap(), "20.1.0");
+        assert_str_eq!(resolve("20.0").unwrap(), "20.0.0");
+        assert_str_eq!(resolve("prefix:21").unwrap(), "21.0.0");
+        assert_str_eq!(resolve("lts-*").unwrap(), "lts-hydrogen");
+        assert_str_eq!(
+            resolve("22").unwrap_err().to_string(),
+            "no versions of mock-prefix match 22, closest available: 20.0.0, 20.1.0, 21.0.0"
+        );
+    }
+
+    #[test]
+    fn test_resolve_constraint() {
+        let forge = MockForge::new("mock-constraint").with_remote_versions(&[
+            "1.1.0", "1.2.0", "1.9.3", "2.0.0", "16.20.2", "18.0.0", "20.11.1",
+        ]);
+        let fa = forge.fa().clone();
+        let resolve = |v: &str| {
+            ToolRequest::new(fa.clone(), v)
+                .unwrap()
+                .resolve(&forge, false, true)
+                .map(|tv| tv.version)
+        };
+        assert_str_eq!(resolve(">=18").unwrap(), "20.11.1");
+        assert_str_eq!(resolve("^1.2").unwrap(), "1.9.3");
+        assert_str_eq!(resolve("~1.1").unwrap(), "1.1.0");
+        assert_str_eq!(
+            resolve(">=21").unwrap_err().to_string(),
+            "no versions of mock-constraint satisfy >=21"
+        );
+        let tr = ToolRequest::new(fa.clone(), "^1.2").unwrap();
+        assert_str_eq!(tr.to_string(), "mock-constraint@^1.2");
+
+        let forge = MockForge::new("mock-constraint-nonsemver")
+            .with_remote_versions(&["lts-gallium", "nightly"]);
+        let fa = forge.fa().clone();
+        let tr = ToolRequest::new(fa, ">=18").unwrap();
+        assert_str_eq!(
+            tr.resolve(&forge, false, true).unwrap_err().to_string(),
+            "mock-constraint-nonsemver does not use semver versions, version constraints like >=18 are not supported"
+        );
+    }
+
+    #[test]
+    fn test_resolve_alias() {
+        let forge = MockForge::new("mock-alias")
+            .with_remote_versions(&["18.20.2", "20.11.1", "21.0.0"])
+            .with_aliases(&[("lts", "20.11.1"), ("lts/hydrogen", "18")]);
+        let fa = forge.fa().clone();
+        let resolve = |v: &str| {
+            ToolRequest::new(fa.clone(), v)
+                .unwrap()
+                .resolve(&forge, false, true)
+                .map(|tv| tv.version)
+        };
+        assert_str_eq!(resolve("lts").unwrap(), "20.11.1");
+        assert_str_eq!(resolve("lts/hydrogen").unwrap(), "18.20.2");
+        assert_str_eq!(
+            resolve("lts/iron").unwrap_err().to_string(),
+            "unknown alias lts/iron for mock-alias, known aliases: lts, lts/hydrogen"
+        );
+        // plugins accept unlisted versions which only share a prefix with an alias
+        assert_str_eq!(resolve("lts-2024-05-01").unwrap(), "lts-2024-05-01");
+    }
+
+    #[test]
+    fn test_resolve_prefer_stable() {
+        let forge = MockForge::new("mock-prerelease").with_remote_versions(&[
+            "1.0.0",
+            "1.1.0",
+            "1.2.0.beta",
+            "1.2.0alpha",
+            "2.0.0.dev0",
+        ]);
+        let fa = forge.fa().clone();
+        let resolve = |v: &str, prefer_stable| {
+            ToolRequest::new(fa.clone(), v)
+                .unwrap()
+                .resolve(&forge, false, prefer_stable)
+                .unwrap()
+                .version
+        };
+        assert_str_eq!(resolve("latest", true), "1.1.0");
+        assert_str_eq!(resolve("1", true), "1.1.0");
+        assert_str_eq!(resolve("1", false), "1.2.0alpha");
+        assert_str_eq!(resolve("1.2.0.beta", true), "1.2.0.beta");
+        assert_str_eq!(resolve("2", true), "2.0.0.dev0");
+    }
+
+    #[test]
+    fn test_is_prerelease() {
+        for v in [
+            "2.0.0-rc1",
+            "1.2.0beta1",
+            "2.0.dev0",
+            "1.0.0-alpha.2",
+            "3.0-pre",
+        ] {
+            assert!(is_prerelease(v), "{v}");
+        }
+        for v in [
+            "1.0.0",
+            "source-1.2.3",
+            "devel",
+            "express-4.19.2",
+            "1.0.0+precise",
+        ] {
+            assert!(!is_prerelease(v), "{v}");
+        }
+    }
+}
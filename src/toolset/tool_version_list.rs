@@ -1,9 +1,13 @@
 use crate::cli::args::ForgeArg;
+use crate::config::Settings;
 use crate::forge;
+use crate::forge::Forge;
 use crate::toolset::tool_version_request::ToolRequest;
-use crate::toolset::{ToolSource, ToolVersion};
+use crate::toolset::{ToolRequestSet, ToolSource, ToolVersion};
 
 /// represents several versions of a tool for a particular plugin
+/// versions are kept in request order, e.g.: with `node = ["20", "18"]` node 20's bin paths come
+/// first in PATH and `which node` is node 20's, see Toolset::which
 #[derive(Debug, Clone)]
 pub struct ToolVersionList {
     pub forge: ForgeArg,
@@ -21,15 +25,38 @@ impl ToolVersionList {
             source,
         }
     }
-    pub fn resolve(&mut self, latest_versions: bool) -> eyre::Result<()> {
+    /// versions locked in the lockfile are preferred over fuzzy matching unless latest_versions
+    /// is set
+    pub fn resolve(
+        &mut self,
+        latest_versions: bool,
+        lockfile: Option<&ToolRequestSet>,
+    ) -> eyre::Result<()> {
         self.versions.clear();
         let plugin = forge::get(&self.forge);
-        for tvr in &mut self.requests {
-            match tvr.resolve(plugin.as_ref(), latest_versions) {
+        let settings = Settings::get();
+        for tvr in &self.requests {
+            let locked = lockfile
+                .filter(|_| !latest_versions)
+                .and_then(|lf| lf.locked(tvr));
+            // expand a copy so the requests keep their original options for re-resolving
+            let resolved = match &locked {
+                Some(locked) => ensure_available(plugin.as_ref(), locked)
+                    .and_then(|_| locked.resolve(plugin.as_ref(), false, settings.prefer_stable)),
+                None => tvr
+                    .clone()
+                    .with_expanded_options(settings.strict_option_vars)
+                    .and_then(|tvr| {
+                        tvr.resolve(plugin.as_ref(), latest_versions, settings.prefer_stable)
+                    }),
+            };
+            match resolved {
                 Ok(v) => self.versions.push(v),
                 Err(err) => {
                     let source = self.source.to_string();
-                    bail!("failed to resolve version of {plugin} from {source}: {err:#}");
+                    return Err(err.wrap_err(format!(
+                        "failed to resolve version of {plugin} from {source}"
+                    )));
                 }
             }
         }
@@ -37,6 +64,21 @@ impl ToolVersionList {
     }
 }
 
+/// locked versions are used as-is so they need to still exist, e.g.: not be yanked
+fn ensure_available(plugin: &dyn Forge, locked: &ToolRequest) -> eyre::Result<()> {
+    let tv = ToolVersion::new(plugin, locked.clone(), locked.version());
+    if !plugin.is_installed()
+        || plugin.is_version_installed(&tv)
+        || plugin.list_remote_versions()?.contains(&tv.version)
+    {
+        return Ok(());
+    }
+    bail!(
+        "{tv} is locked in mise.lock but is no longer available, \
+        remove it from mise.lock to resolve it again"
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{dirs, env, file};
@@ -48,7 +90,7 @@ mod tests {
         let fa: ForgeArg = "tiny".into();
         let mut tvl = ToolVersionList::new(fa.clone(), ToolSource::Argument);
         tvl.requests.push(ToolRequest::new(fa, "latest").unwrap());
-        tvl.resolve(true).unwrap();
+        tvl.resolve(true, None).unwrap();
         assert_eq!(tvl.versions.len(), 1);
     }
 
@@ -60,7 +102,7 @@ mod tests {
         let fa: ForgeArg = "dummy".into();
         let mut tvl = ToolVersionList::new(fa.clone(), ToolSource::Argument);
         tvl.requests.push(ToolRequest::new(fa, "latest").unwrap());
-        let _ = tvl.resolve(true);
+        let _ = tvl.resolve(true, None);
         assert_eq!(tvl.versions.len(), 0);
         env::remove_var("MISE_FAILURE");
     }
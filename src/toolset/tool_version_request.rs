@@ -2,13 +2,14 @@ use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
 use eyre::Result;
+use semver::VersionReq;
 use versions::{Chunk, Version};
 use xx::file;
 
 use crate::cli::args::ForgeArg;
-use crate::forge;
 use crate::forge::Forge;
 use crate::toolset::{ToolVersion, ToolVersionOptions};
+use crate::{env, forge};
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum ToolRequest {
@@ -27,6 +28,13 @@ pub enum ToolRequest {
         ref_: String,
         options: ToolVersionOptions,
     },
+    /// a semver constraint like `>=18`, `~20.1` or `^1.2`, constraint is kept as written
+    Constraint {
+        forge: ForgeArg,
+        constraint: String,
+        req: VersionReq,
+        options: ToolVersionOptions,
+    },
     Sub {
         forge: ForgeArg,
         sub: String,
@@ -62,6 +70,15 @@ impl ToolRequest {
             None => {
                 if s == "system" {
                     Self::System(forge)
+                } else if s.starts_with(['>', '<', '=', '~', '^']) {
+                    let req = VersionReq::parse(&s)
+                        .map_err(|e| eyre!("invalid version constraint {s}: {e}"))?;
+                    Self::Constraint {
+                        forge,
+                        constraint: s,
+                        req,
+                        options: Default::default(),
+                    }
                 } else {
                     Self::Version {
                         forge,
@@ -78,16 +95,33 @@ impl ToolRequest {
         match &mut tvr {
             Self::Version { options: o, .. }
             | Self::Prefix { options: o, .. }
-            | Self::Ref { options: o, .. } => *o = options,
+            | Self::Ref { options: o, .. }
+            | Self::Constraint { options: o, .. } => *o = options,
             _ => Default::default(),
         }
         Ok(tvr)
     }
+    /// adds the defaults which aren't already set, see Settings::forge_default_options
+    pub fn with_default_options(mut self, defaults: &ToolVersionOptions) -> Self {
+        match &mut self {
+            Self::Version { options: o, .. }
+            | Self::Prefix { options: o, .. }
+            | Self::Ref { options: o, .. }
+            | Self::Constraint { options: o, .. } => {
+                for (k, v) in defaults {
+                    o.entry(k.clone()).or_insert_with(|| v.clone());
+                }
+            }
+            _ => (),
+        }
+        self
+    }
     pub fn forge(&self) -> &ForgeArg {
         match self {
             Self::Version { forge: f, .. }
             | Self::Prefix { forge: f, .. }
             | Self::Ref { forge: f, .. }
+            | Self::Constraint { forge: f, .. }
             | Self::Path(f, _)
             | Self::Sub { forge: f, .. }
             | Self::System(f) => f,
@@ -102,6 +136,7 @@ impl ToolRequest {
             Self::Version { version: v, .. } => v.clone(),
             Self::Prefix { prefix: p, .. } => format!("prefix:{p}"),
             Self::Ref { ref_: r, .. } => format!("ref:{r}"),
+            Self::Constraint { constraint: c, .. } => c.clone(),
             Self::Path(_, p) => format!("path:{}", p.display()),
             Self::Sub {
                 sub, orig_version, ..
@@ -114,11 +149,35 @@ impl ToolRequest {
         match self {
             Self::Version { options: o, .. }
             | Self::Prefix { options: o, .. }
-            | Self::Ref { options: o, .. } => o.clone(),
+            | Self::Ref { options: o, .. }
+            | Self::Constraint { options: o, .. } => o.clone(),
             _ => Default::default(),
         }
     }
 
+    /// expands `$VAR` and `${VAR}` in the option values with the process env, e.g.:
+    /// `configure_opts = "--prefix=$HOME/foo"`, see expand_vars
+    pub fn with_expanded_options(mut self, strict: bool) -> Result<Self> {
+        match &mut self {
+            Self::Version { options: o, .. }
+            | Self::Prefix { options: o, .. }
+            | Self::Ref { options: o, .. }
+            | Self::Constraint { options: o, .. } => {
+                for v in o.values_mut() {
+                    *v = expand_vars(v, |k| env::var(k).ok(), strict)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(self)
+    }
+
+    /// set with the `optional=true` option for tools which are only needed on some machines,
+    /// these aren't warned about when missing
+    pub fn is_optional(&self) -> bool {
+        self.options().get("optional").is_some_and(|v| v == "true")
+    }
+
     pub fn is_installed(&self) -> bool {
         // TODO: dispatch to forge
         match self {
@@ -131,7 +190,9 @@ impl ToolRequest {
         match self {
             Self::Version { forge, version, .. } => Some(forge.installs_path.join(version)),
             Self::Ref { forge, ref_, .. } => {
-                Some(forge.installs_path.join(format!("ref-{}", ref_)))
+                // must match ToolVersion::tv_pathname so branches like "feat/x" are found
+                let pathname = format!("ref-{}", ref_).replace([':', '/'], "-");
+                Some(forge.installs_path.join(pathname))
             }
             Self::Sub {
                 forge,
@@ -149,6 +210,17 @@ impl ToolRequest {
                 }
                 Err(_) => None,
             },
+            Self::Constraint { forge, req, .. } => match file::ls(&forge.installs_path) {
+                Ok(installs) => installs
+                    .iter()
+                    .filter_map(|p| {
+                        let v = parse_semver(&p.to_string_lossy())?;
+                        req.matches(&v).then_some((v, p))
+                    })
+                    .max_by(|(a, _), (b, _)| a.cmp(b))
+                    .map(|(_, p)| forge.installs_path.join(p)),
+                Err(_) => None,
+            },
             Self::Path(_, path) => Some(path.clone()),
             Self::System(_) => None,
         }
@@ -166,8 +238,13 @@ impl ToolRequest {
         Ok(None)
     }
 
-    pub fn resolve(&self, plugin: &dyn Forge, latest_versions: bool) -> Result<ToolVersion> {
-        ToolVersion::resolve(plugin, self.clone(), latest_versions)
+    pub fn resolve(
+        &self,
+        plugin: &dyn Forge,
+        latest_versions: bool,
+        prefer_stable: bool,
+    ) -> Result<ToolVersion> {
+        ToolVersion::resolve(plugin, self.clone(), latest_versions, prefer_stable)
     }
 }
 
@@ -187,6 +264,70 @@ pub fn version_sub(orig: &str, sub: &str) -> String {
     orig.to_string()
 }
 
+/// shell-style expansion of `$VAR` and `${VAR}`, `\$` is a literal `$`
+/// like in a shell, undefined variables expand to an empty string unless strict
+pub fn expand_vars(
+    s: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+    strict: bool,
+) -> Result<String> {
+    let mut out = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'$') => {
+                chars.next();
+                out.push('$');
+            }
+            '$' => {
+                let mut name = String::new();
+                if chars.next_if_eq(&'{').is_some() {
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+                    ensure!(closed, "missing }} in {s}");
+                } else {
+                    while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                        name.push(c);
+                    }
+                }
+                if name.is_empty() {
+                    out.push('$');
+                    continue;
+                }
+                match lookup(&name) {
+                    Some(v) => out.push_str(&v),
+                    None if strict => bail!("{name} is not set in {s}"),
+                    None => debug!("{name} is not set in {s}, expanding it to an empty string"),
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// parses versions loosely as semver for constraints, "v18", "18" and "18.1" are accepted as
+/// "18.0.0" and "18.1.0"
+pub fn parse_semver(v: &str) -> Option<semver::Version> {
+    let v = v.strip_prefix('v').unwrap_or(v);
+    let (core, rest) = match v.find(['-', '+']) {
+        Some(i) => v.split_at(i),
+        None => (v, ""),
+    };
+    let mut parts = core.split('.').collect::<Vec<_>>();
+    if parts.len() > 3 || parts.iter().any(|p| p.parse::<u64>().is_err()) {
+        return None;
+    }
+    parts.resize(3, "0");
+    semver::Version::parse(&format!("{}{rest}", parts.join("."))).ok()
+}
+
 impl Display for ToolRequest {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}@{}", &self.forge(), self.version())
@@ -195,11 +336,88 @@ impl Display for ToolRequest {
 
 #[cfg(test)]
 mod tests {
-    use super::version_sub;
+    use super::*;
 
     #[test]
     fn test_version_sub() {
         assert_str_eq!(version_sub("18.2.3", "2"), "16");
         assert_str_eq!(version_sub("18.2.3", "0.1"), "18.1");
     }
+
+    #[test]
+    fn test_ref() {
+        let fa: ForgeArg = "tiny".into();
+        for s in ["ref:main", "ref-main"] {
+            let tvr = ToolRequest::new(fa.clone(), s).unwrap();
+            assert!(matches!(&tvr, ToolRequest::Ref { ref_, .. } if ref_ == "main"));
+            assert_str_eq!(tvr.to_string(), "tiny@ref:main");
+            assert_eq!(ToolRequest::new(fa.clone(), &tvr.version()).unwrap(), tvr);
+        }
+        let tvr = ToolRequest::new(fa.clone(), "ref:feat/x").unwrap();
+        assert_eq!(
+            tvr.install_path(),
+            Some(fa.installs_path.join("ref-feat-x"))
+        );
+    }
+
+    #[test]
+    fn test_constraint() {
+        let fa: ForgeArg = "tiny".into();
+        let tvr = ToolRequest::new(fa.clone(), ">=18, <20").unwrap();
+        assert!(
+            matches!(&tvr, ToolRequest::Constraint { constraint, .. } if constraint == ">=18, <20")
+        );
+        assert_str_eq!(tvr.to_string(), "tiny@>=18, <20");
+        assert_eq!(ToolRequest::new(fa.clone(), &tvr.version()).unwrap(), tvr);
+        assert!(ToolRequest::new(fa, ">=x").is_err());
+        assert_eq!(parse_semver("v18"), Some(semver::Version::new(18, 0, 0)));
+        assert_eq!(parse_semver("lts-hydrogen"), None);
+    }
+
+    #[test]
+    fn test_expand_vars() {
+        let lookup = |k: &str| (k == "FOO").then(|| "bar".to_string());
+        let expand = |s| expand_vars(s, lookup, false).unwrap();
+        assert_str_eq!(expand("--prefix=$FOO/baz"), "--prefix=bar/baz");
+        assert_str_eq!(expand("${FOO}baz"), "barbaz");
+        assert_str_eq!(expand("a${UNDEFINED}b"), "ab");
+        assert_str_eq!(expand(r"\$FOO costs 5$ or $"), "$FOO costs 5$ or $");
+        assert_str_eq!(
+            expand_vars("${UNDEFINED}", lookup, true)
+                .unwrap_err()
+                .to_string(),
+            "UNDEFINED is not set in ${UNDEFINED}"
+        );
+        assert!(expand_vars("${FOO", lookup, false).is_err());
+
+        let options = ToolVersionOptions::from([(
+            "configure_opts".to_string(),
+            "--prefix=$HOME/foo".to_string(),
+        )]);
+        let tvr = ToolRequest::new_opts("tiny".into(), "1", options)
+            .unwrap()
+            .with_expanded_options(false)
+            .unwrap();
+        let expected = format!("--prefix={}/foo", env::var("HOME").unwrap());
+        assert_str_eq!(tvr.options()["configure_opts"], expected);
+    }
+
+    #[test]
+    fn test_with_default_options() {
+        let defaults = ToolVersionOptions::from([
+            ("locked".to_string(), "true".to_string()),
+            ("features".to_string(), "default".to_string()),
+        ]);
+        let tvr = ToolRequest::new("cargo:eza".into(), "0.18")
+            .unwrap()
+            .with_default_options(&defaults);
+        assert_eq!(tvr.options(), defaults);
+
+        let options = ToolVersionOptions::from([("locked".to_string(), "false".to_string())]);
+        let tvr = ToolRequest::new_opts("cargo:eza".into(), "0.18", options)
+            .unwrap()
+            .with_default_options(&defaults);
+        assert_str_eq!(tvr.options()["locked"], "false");
+        assert_str_eq!(tvr.options()["features"], "default");
+    }
 }
@@ -0,0 +1,97 @@
+use std::fmt::{Display, Formatter};
+
+use itertools::Itertools;
+
+use crate::cli::args::ForgeArg;
+use crate::toolset::{ToolRequest, Toolset};
+
+/// the tools and version requests which differ between two toolsets, see Toolset::diff
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ToolsetDiff {
+    pub added: Vec<(ForgeArg, Vec<ToolRequest>)>,
+    pub removed: Vec<(ForgeArg, Vec<ToolRequest>)>,
+    /// (tool, old requests, new requests)
+    pub changed: Vec<(ForgeArg, Vec<ToolRequest>, Vec<ToolRequest>)>,
+}
+
+impl ToolsetDiff {
+    pub fn new(old: &Toolset, new: &Toolset) -> Self {
+        let mut diff = Self::default();
+        for (fa, tvl) in &new.versions {
+            match old.versions.get(fa) {
+                Some(old_tvl) if old_tvl.requests != tvl.requests => {
+                    diff.changed
+                        .push((fa.clone(), old_tvl.requests.clone(), tvl.requests.clone()))
+                }
+                Some(_) => {}
+                None => diff.added.push((fa.clone(), tvl.requests.clone())),
+            }
+        }
+        for (fa, tvl) in &old.versions {
+            if !new.versions.contains_key(fa) {
+                diff.removed.push((fa.clone(), tvl.requests.clone()));
+            }
+        }
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// e.g.: "+node@20, -python@3.11, tiny@2 -> tiny@3"
+impl Display for ToolsetDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+        let added = self
+            .added
+            .iter()
+            .map(|(_, requests)| format!("+{}", requests.iter().join(" ")));
+        let removed = self
+            .removed
+            .iter()
+            .map(|(_, requests)| format!("-{}", requests.iter().join(" ")));
+        let changed = self
+            .changed
+            .iter()
+            .map(|(_, old, new)| format!("{} -> {}", old.iter().join(" "), new.iter().join(" ")));
+        write!(f, "{}", added.chain(removed).chain(changed).join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::toolset::ToolSource;
+
+    use super::*;
+
+    #[test]
+    fn test_toolset_diff() {
+        let toolset = |versions: &[(&str, &str)]| {
+            let mut ts = Toolset::new(ToolSource::Argument);
+            for (tool, v) in versions {
+                ts.add_version(ToolRequest::new((*tool).into(), v).unwrap());
+            }
+            ts
+        };
+        let old = toolset(&[("tiny", "1")]);
+        assert!(old.diff(&old).is_empty());
+        assert_str_eq!(old.diff(&old).to_string(), "no changes");
+
+        let new = toolset(&[("tiny", "1"), ("dummy", "2")]);
+        let diff = old.diff(&new);
+        assert_eq!(diff.added.len(), 1);
+        assert_str_eq!(diff.to_string(), "+dummy@2");
+
+        let diff = new.diff(&old);
+        assert_eq!(diff.removed.len(), 1);
+        assert_str_eq!(diff.to_string(), "-dummy@2");
+
+        let diff = old.diff(&toolset(&[("tiny", "2")]));
+        assert_eq!(diff.changed.len(), 1);
+        assert_str_eq!(diff.to_string(), "tiny@1 -> tiny@2");
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+
+use crate::cli::args::ForgeArg;
+use crate::toolset::ToolVersion;
+
+/// cache of Toolset::which results by bin name so commands which look up many bins, e.g.: shell
+/// completion, don't ask every installed version for each of them
+/// misses are cached as well, invalidated when the toolset is resolved or after installing
+#[derive(Default)]
+pub struct WhichCache {
+    entries: Mutex<HashMap<String, Option<(ForgeArg, ToolVersion)>>>,
+}
+
+impl WhichCache {
+    /// the cached result, None if bin_name hasn't been looked up yet
+    pub fn get(&self, bin_name: &str) -> Option<Option<(ForgeArg, ToolVersion)>> {
+        self.entries.lock().unwrap().get(bin_name).cloned()
+    }
+
+    pub fn insert(&self, bin_name: &str, found: Option<(ForgeArg, ToolVersion)>) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(bin_name.to_string(), found);
+    }
+
+    pub fn invalidate(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// a cloned toolset is usually modified afterwards so it starts with an empty cache
+impl Clone for WhichCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Debug for WhichCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WhichCache").finish_non_exhaustive()
+    }
+}
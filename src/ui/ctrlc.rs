@@ -1,5 +1,6 @@
 use std::process::exit;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::thread;
 
 use console::Term;
@@ -10,6 +11,13 @@ use signal_hook::iterator::{Handle, Signals};
 #[derive(Debug)]
 pub struct HandleGuard(Handle);
 
+#[must_use]
+#[derive(Debug)]
+pub struct CancelGuard(Handle);
+
+/// set while cancel_on_ctrlc is active so handle_ctrlc doesn't exit
+static CANCELLABLE: AtomicBool = AtomicBool::new(false);
+
 /// ensures cursor is displayed on ctrl-c
 pub fn handle_ctrlc() -> eyre::Result<Option<HandleGuard>> {
     static HANDLED: AtomicBool = AtomicBool::new(false);
@@ -21,8 +29,12 @@ pub fn handle_ctrlc() -> eyre::Result<Option<HandleGuard>> {
     let mut signals = Signals::new([SIGINT])?;
     let handle = HandleGuard(signals.handle());
     thread::spawn(move || {
-        if signals.into_iter().next().is_some() {
+        for _ in signals.forever() {
             let _ = Term::stderr().show_cursor();
+            if CANCELLABLE.load(Ordering::Relaxed) {
+                // cancel_on_ctrlc decides whether to exit
+                continue;
+            }
             debug!("Ctrl-C pressed, exiting...");
             exit(1);
         }
@@ -31,8 +43,34 @@ pub fn handle_ctrlc() -> eyre::Result<Option<HandleGuard>> {
     Ok(Some(handle))
 }
 
+/// sets cancel on ctrl-c instead of exiting so the work in progress can wind down
+/// pressing ctrl-c again exits right away
+pub fn cancel_on_ctrlc(cancel: Arc<AtomicBool>) -> eyre::Result<CancelGuard> {
+    let mut signals = Signals::new([SIGINT])?;
+    let handle = CancelGuard(signals.handle());
+    CANCELLABLE.store(true, Ordering::Relaxed);
+    thread::spawn(move || {
+        for _ in signals.forever() {
+            if cancel.swap(true, Ordering::Relaxed) {
+                let _ = Term::stderr().show_cursor();
+                debug!("Ctrl-C pressed again, exiting...");
+                exit(1);
+            }
+            warn!("Ctrl-C pressed, cancelling after the current installs finish");
+        }
+    });
+    Ok(handle)
+}
+
 impl Drop for HandleGuard {
     fn drop(&mut self) {
         self.0.close();
     }
 }
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        CANCELLABLE.store(false, Ordering::Relaxed);
+        self.0.close();
+    }
+}